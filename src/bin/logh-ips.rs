@@ -0,0 +1,50 @@
+//! マップ定義の座標と隣接惑星の表を ROM イメージに書き込む IPS パッチを作る。
+//!
+//! 使い方: `logh-ips <ROM> <配置の JSON> <マップ定義の JSON> <出力する IPS>`
+//!
+//! 配置の JSON の形式は [`logh_travel::rom`] を参照。
+
+use std::process::ExitCode;
+
+use logh_travel::ips::IpsPatch;
+use logh_travel::rom::{self, RomLayout};
+use logh_travel::starmap::StarMap;
+
+fn main() -> ExitCode {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let [rom_path, layout_path, map_path, out_path] = &args[..] else {
+        eprintln!("使い方: logh-ips <ROM> <配置の JSON> <マップ定義の JSON> <出力する IPS>");
+        eprintln!("配置の JSON の形式は logh_travel::rom のドキュメントを参照。");
+        return ExitCode::from(2);
+    };
+
+    match run(rom_path, layout_path, map_path, out_path) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("エラー: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(
+    rom_path: &str,
+    layout_path: &str,
+    map_path: &str,
+    out_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(rom_path)?;
+    let layout = RomLayout::from_json(&std::fs::read_to_string(layout_path)?)?;
+    let map = StarMap::from_json(&std::fs::read_to_string(map_path)?)?;
+
+    let patched = rom::write_tables(&bytes, &layout, &map)?;
+    let patch = IpsPatch::diff(&bytes, &patched)?;
+    std::fs::write(out_path, patch.to_bytes())?;
+
+    println!("レコード数: {}", patch.records().len());
+    for record in patch.records() {
+        println!("{:#08x}: {} バイト", record.offset, record.data.len());
+    }
+
+    Ok(())
+}
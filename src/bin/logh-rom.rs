@@ -0,0 +1,77 @@
+//! ROM イメージからマップの表を抽出し、組み込みのマップ (または指定したマップ定義) と比較する。
+//!
+//! 使い方: `logh-rom <ROM> <配置の JSON> [マップ定義の JSON]`
+//!
+//! 相違がなければ終了コード 0、相違があれば 1、エラーなら 2 で終了する。
+//!
+//! 配置の JSON の形式は [`logh_travel::rom`] を参照。
+
+use std::process::ExitCode;
+
+use logh_travel::geometry::Vec3;
+use logh_travel::locale::Language;
+use logh_travel::rom::{self, INesRom, RomLayout};
+use logh_travel::starmap::StarMap;
+
+fn main() -> ExitCode {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let (rom_path, layout_path, map_path) = match &args[..] {
+        [rom, layout] => (rom, layout, None),
+        [rom, layout, map] => (rom, layout, Some(map)),
+        _ => {
+            eprintln!("使い方: logh-rom <ROM> <配置の JSON> [マップ定義の JSON]");
+            eprintln!("配置の JSON の形式は logh_travel::rom のドキュメントを参照。");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(rom_path, layout_path, map_path) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("エラー: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// 相違がなければ `true` を返す。
+fn run(
+    rom_path: &str,
+    layout_path: &str,
+    map_path: Option<&String>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let rom = INesRom::parse(&std::fs::read(rom_path)?)?;
+    let layout = RomLayout::from_json(&std::fs::read_to_string(layout_path)?)?;
+    let map = match map_path {
+        Some(path) => StarMap::from_json(&std::fs::read_to_string(path)?)?,
+        None => StarMap::builtin().clone(),
+    };
+
+    let planets = rom::extract(&rom, &layout)?;
+    let language = layout
+        .names
+        .as_ref()
+        .and_then(|names| Language::from_code(&names.language))
+        .unwrap_or_default();
+    let diffs = rom::diff(&planets, &map, language);
+
+    println!("マッパー: {}", rom.mapper());
+    println!("抽出した惑星数: {}", planets.len());
+    for (id, planet) in planets.iter().enumerate() {
+        let Vec3 { x, y, z } = planet.position;
+        let name = planet.name.as_deref().unwrap_or("-");
+        println!("{id}: {name} ({x}, {y}, {z}) {:?}", planet.neighbors);
+    }
+
+    if diffs.is_empty() {
+        println!("マップとの相違なし");
+    } else {
+        println!("マップとの相違: {} 件", diffs.len());
+        for d in &diffs {
+            println!("{d}");
+        }
+    }
+
+    Ok(diffs.is_empty())
+}
@@ -0,0 +1,61 @@
+//! 惑星を名前または ID で検索し、良い一致の順に表示する。
+//!
+//! 使い方: `logh-search <検索語> [マップ定義の JSON]`
+//!
+//! 一致する惑星がなければ終了コード 1、エラーなら 2 で終了する。
+
+use std::process::ExitCode;
+
+use logh_travel::geometry::Vec3;
+use logh_travel::locale::Language;
+use logh_travel::search::{search_planets, MatchKind};
+use logh_travel::starmap::StarMap;
+
+fn main() -> ExitCode {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let (query, map_path) = match &args[..] {
+        [query] => (query, None),
+        [query, map] => (query, Some(map)),
+        _ => {
+            eprintln!("使い方: logh-search <検索語> [マップ定義の JSON]");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(query, map_path) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("エラー: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// 一致する惑星があれば `true` を返す。
+fn run(query: &str, map_path: Option<&String>) -> Result<bool, Box<dyn std::error::Error>> {
+    let map = match map_path {
+        Some(path) => StarMap::from_json(&std::fs::read_to_string(path)?)?,
+        None => StarMap::builtin().clone(),
+    };
+
+    let matches = search_planets(&map, query);
+    for m in &matches {
+        let planet = map.planet(m.id);
+        let Vec3 { x, y, z } = planet.position();
+        let kind = match m.kind {
+            MatchKind::Exact => "完全一致",
+            MatchKind::Prefix => "前方一致",
+            MatchKind::Substring => "部分一致",
+            MatchKind::Subsequence => "あいまい",
+        };
+        println!(
+            "{}: {} / {} ({x}, {y}, {z}) [{kind}]",
+            m.id,
+            planet.name(Language::Japanese),
+            planet.name(Language::English),
+        );
+    }
+
+    Ok(!matches.is_empty())
+}
@@ -0,0 +1,187 @@
+use std::fmt::Write as _;
+
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+/// 表形式データの出力形式。
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Format {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl Format {
+    pub fn iter() -> impl Iterator<Item = Self> + Clone {
+        use Format::*;
+
+        [Csv, Json, Markdown].into_iter()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Json => "JSON",
+            Self::Markdown => "Markdown",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Markdown => "md",
+        }
+    }
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Json => "application/json",
+            Self::Markdown => "text/markdown",
+        }
+    }
+}
+
+/// 表の 1 セル。
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Cell {
+    Int(u32),
+    Bool(bool),
+    Text(String),
+    /// 値なし。JSON では `null` になる。
+    Empty,
+}
+
+impl From<u32> for Cell {
+    fn from(x: u32) -> Self {
+        Self::Int(x)
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(x: bool) -> Self {
+        Self::Bool(x)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(x: &str) -> Self {
+        Self::Text(x.to_owned())
+    }
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(x: Option<T>) -> Self {
+        x.map_or(Self::Empty, Into::into)
+    }
+}
+
+/// 列名付きの表。各形式の文字列に変換できる。
+#[derive(Clone, Debug)]
+pub struct Table {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new(columns: &[&'static str]) -> Self {
+        Self {
+            columns: columns.to_vec(),
+            rows: vec![],
+        }
+    }
+
+    /// 行を追加する。セル数は列数と一致していなければならない。
+    pub fn push_row(&mut self, row: Vec<Cell>) {
+        assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    pub fn to_string(&self, format: Format) -> String {
+        match format {
+            Format::Csv => self.to_csv(),
+            Format::Json => self.to_json(),
+            Format::Markdown => self.to_markdown(),
+        }
+    }
+
+    /// ヘッダ行付きの CSV を返す。
+    fn to_csv(&self) -> String {
+        fn field(cell: &Cell) -> String {
+            match cell {
+                Cell::Int(x) => x.to_string(),
+                Cell::Bool(x) => x.to_string(),
+                Cell::Text(x) if x.contains([',', '"', '\n', '\r']) => {
+                    format!("\"{}\"", x.replace('"', "\"\""))
+                }
+                Cell::Text(x) => x.clone(),
+                Cell::Empty => String::new(),
+            }
+        }
+
+        let mut s = self.columns.join(",");
+        s.push('\n');
+        for row in &self.rows {
+            let fields: Vec<_> = row.iter().map(field).collect();
+            s.push_str(&fields.join(","));
+            s.push('\n');
+        }
+
+        s
+    }
+
+    /// 列名をキーとするオブジェクトの配列を返す。
+    fn to_json(&self) -> String {
+        let rows: Vec<_> = self
+            .rows
+            .iter()
+            .map(|cells| Row {
+                columns: &self.columns,
+                cells,
+            })
+            .collect();
+
+        serde_json::to_string(&rows).unwrap() + "\n"
+    }
+
+    /// GitHub Flavored Markdown の表を返す。
+    fn to_markdown(&self) -> String {
+        fn field(cell: &Cell) -> String {
+            match cell {
+                Cell::Int(x) => x.to_string(),
+                Cell::Bool(x) => x.to_string(),
+                Cell::Text(x) => x.replace('|', "\\|"),
+                Cell::Empty => String::new(),
+            }
+        }
+
+        let mut s = String::new();
+        writeln!(s, "| {} |", self.columns.join(" | ")).unwrap();
+        writeln!(s, "|{}", " --- |".repeat(self.columns.len())).unwrap();
+        for row in &self.rows {
+            let fields: Vec<_> = row.iter().map(field).collect();
+            writeln!(s, "| {} |", fields.join(" | ")).unwrap();
+        }
+
+        s
+    }
+}
+
+/// 列の順にメンバを並べた JSON のオブジェクトとして表す 1 行。
+struct Row<'a> {
+    columns: &'a [&'static str],
+    cells: &'a [Cell],
+}
+
+impl Serialize for Row<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for (column, cell) in self.columns.iter().zip(self.cells) {
+            map.serialize_entry(column, cell)?;
+        }
+
+        map.end()
+    }
+}
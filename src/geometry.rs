@@ -1,4 +1,8 @@
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+/// 座標。マップ定義ファイルでは `[x, y, z]` と表す。
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[serde(from = "[u32; 3]", into = "[u32; 3]")]
 pub struct Vec3 {
     pub x: u32,
     pub y: u32,
@@ -11,14 +15,111 @@ impl Vec3 {
     }
 
     pub fn distance(self, other: Self) -> u32 {
-        num_integer::sqrt(self.distance_squared(other))
+        self.distance_by(other, SqrtRoutine::Exact)
+    }
+
+    /// 平方根の計算方法を指定して距離を返す。
+    ///
+    /// 検証済みのマップでは座標は `starmap::COORD_MAX` 以下で、距離の 2 乗は 32 ビットに収まる。
+    /// 収まらない場合は平方根の計算方法によらず切り捨てで求める。
+    pub fn distance_by(self, other: Self, sqrt: SqrtRoutine) -> u32 {
+        let d2 = self.distance_squared(other);
+        match u32::try_from(d2) {
+            Ok(d2) => sqrt.sqrt(d2),
+            Err(_) => u32::try_from(num_integer::sqrt(d2)).unwrap_or(u32::MAX),
+        }
+    }
+
+    fn distance_squared(self, other: Self) -> u64 {
+        let d2 = |a: u32, b: u32| u64::from(a.abs_diff(b)).pow(2);
+
+        d2(self.x, other.x)
+            .saturating_add(d2(self.y, other.y))
+            .saturating_add(d2(self.z, other.z))
+    }
+}
+
+impl From<[u32; 3]> for Vec3 {
+    fn from([x, y, z]: [u32; 3]) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl From<Vec3> for [u32; 3] {
+    fn from(v: Vec3) -> Self {
+        [v.x, v.y, v.z]
+    }
+}
+
+/// 距離の計算に使う整数の平方根の計算方法。
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum SqrtRoutine {
+    /// 切り捨て。
+    #[default]
+    Exact,
+    /// 四捨五入。
+    ///
+    /// 16 ビットの開平法 (1 ビットずつ根を確定させる方法) の後に余りで四捨五入する。
+    /// 切り捨てとの比較に使うもので、ゲームの平方根ルーチンを再現したものではない。
+    /// ゲームのルーチンは ROM の逆アセンブルがないため再現していない。
+    Rounded,
+}
+
+impl SqrtRoutine {
+    pub fn iter() -> impl Iterator<Item = Self> + Clone {
+        use SqrtRoutine::*;
+
+        [Exact, Rounded].into_iter()
     }
 
-    fn distance_squared(self, other: Self) -> u32 {
-        let dx2 = self.x.abs_diff(other.x).pow(2);
-        let dy2 = self.y.abs_diff(other.y).pow(2);
-        let dz2 = self.z.abs_diff(other.z).pow(2);
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Exact => "exact",
+            Self::Rounded => "rounded",
+        }
+    }
 
-        dx2 + dy2 + dz2
+    pub fn sqrt(self, n: u32) -> u32 {
+        match self {
+            Self::Exact => num_integer::sqrt(n),
+            Self::Rounded => rounded_sqrt(n),
+        }
     }
 }
+
+/// 16 ビットの開平法と四捨五入による平方根。[`SqrtRoutine::Rounded`] を参照。
+///
+/// 各座標の差が 128 以下なら 2 乗和は 16 ビットに収まる。収まらない入力は座標の範囲内では現れないので、
+/// 同じ丸め方を 32 ビットに広げて計算する。
+fn rounded_sqrt(n: u32) -> u32 {
+    let Ok(n) = u16::try_from(n) else {
+        let root = num_integer::sqrt(n);
+        return if n - root * root > root {
+            root + 1
+        } else {
+            root
+        };
+    };
+
+    // 上位から 2 ビットずつ余りに下ろし、根の次のビットを決める。
+    let mut n = n;
+    let mut root: u16 = 0;
+    let mut rem: u16 = 0;
+    for _ in 0..8 {
+        rem = (rem << 2) | (n >> 14);
+        n <<= 2;
+        root <<= 1;
+        let trial = (root << 1) | 1;
+        if rem >= trial {
+            rem -= trial;
+            root |= 1;
+        }
+    }
+
+    // 余りが根より大きければ、真の平方根の小数部は 0.5 以上。
+    if rem > root {
+        root += 1;
+    }
+
+    u32::from(root)
+}
@@ -0,0 +1,435 @@
+//! 惑星の隣接関係を無向グラフとみなした解析と、外部のグラフツール向けの出力。
+//!
+//! 隣接関係は対称であることを前提とする (`validate` でエラーとなるマップは扱わない)。
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+
+use crate::locale::Language;
+use crate::starmap::StarMap;
+use crate::travel::MovementRules;
+
+/// グラフの出力形式。
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GraphFormat {
+    /// Graphviz の DOT 言語。
+    Dot,
+    GraphMl,
+}
+
+impl GraphFormat {
+    pub fn iter() -> impl Iterator<Item = Self> + Clone {
+        use GraphFormat::*;
+
+        [Dot, GraphMl].into_iter()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dot => "DOT",
+            Self::GraphMl => "GraphML",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Dot => "dot",
+            Self::GraphMl => "graphml",
+        }
+    }
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Dot => "text/vnd.graphviz",
+            Self::GraphMl => "application/graphml+xml",
+        }
+    }
+}
+
+/// 惑星を頂点、隣接関係を辺とする無向グラフを出力する。
+///
+/// 頂点は惑星名・座標・体積・マップ上の位置を、辺は距離と移動力 `speed` での実所要ターン数を持つ。
+/// 辺の距離とターン数は `rules` で計算する。頂点のラベルは `lang` の惑星名とする。
+pub fn export(
+    map: &StarMap,
+    rules: &dyn MovementRules,
+    speed: u32,
+    format: GraphFormat,
+    lang: Language,
+) -> String {
+    match format {
+        GraphFormat::Dot => to_dot(map, rules, speed, lang),
+        GraphFormat::GraphMl => to_graphml(map, rules, speed, lang),
+    }
+}
+
+fn to_dot(map: &StarMap, rules: &dyn MovementRules, speed: u32, lang: Language) -> String {
+    fn quote(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    let mut s = String::new();
+    writeln!(s, "graph {} {{", quote(map.name())).unwrap();
+    for (id, planet) in map.planets().iter().enumerate() {
+        let pos = planet.position();
+        let (mx, my) = planet.map_position();
+        // pos は neato などでマップ画像と同じ配置にするためのもの。DOT の y 軸は上向き。
+        writeln!(
+            s,
+            "  {id} [label={}, name={}, name_en={}, x={}, y={}, z={}, volume={}, pos=\"{mx},{}!\"];",
+            quote(&format!("{id}:{}", planet.name(lang))),
+            quote(planet.name(Language::Japanese)),
+            quote(planet.name(Language::English)),
+            pos.x,
+            pos.y,
+            pos.z,
+            quote(planet.volume().key()),
+            -i64::from(my),
+        )
+        .unwrap();
+    }
+    for (id1, id2) in map.edges() {
+        let (distance, turns) = edge_weights(map, rules, id1, id2, speed);
+        writeln!(
+            s,
+            "  {id1} -- {id2} [distance={distance}, turns={turns}, label=\"{distance}\"];"
+        )
+        .unwrap();
+    }
+    s.push_str("}\n");
+
+    s
+}
+
+fn to_graphml(map: &StarMap, rules: &dyn MovementRules, speed: u32, lang: Language) -> String {
+    const NODE_KEYS: [(&str, &str); 9] = [
+        ("label", "string"),
+        ("name", "string"),
+        ("name_en", "string"),
+        ("x", "int"),
+        ("y", "int"),
+        ("z", "int"),
+        ("volume", "string"),
+        ("map_x", "int"),
+        ("map_y", "int"),
+    ];
+    const EDGE_KEYS: [(&str, &str); 2] = [("distance", "int"), ("turns", "int")];
+
+    fn data(s: &mut String, key: &str, value: impl ToString) {
+        writeln!(
+            s,
+            "      <data key=\"{key}\">{}</data>",
+            xml_escape(&value.to_string())
+        )
+        .unwrap();
+    }
+
+    let mut s = String::new();
+    s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    s.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for (domain, keys) in [("node", &NODE_KEYS[..]), ("edge", &EDGE_KEYS[..])] {
+        for (key, ty) in keys {
+            writeln!(
+                s,
+                "  <key id=\"{key}\" for=\"{domain}\" attr.name=\"{key}\" attr.type=\"{ty}\"/>"
+            )
+            .unwrap();
+        }
+    }
+    writeln!(
+        s,
+        "  <graph id=\"{}\" edgedefault=\"undirected\">",
+        xml_escape(map.name())
+    )
+    .unwrap();
+    for (id, planet) in map.planets().iter().enumerate() {
+        let pos = planet.position();
+        let (mx, my) = planet.map_position();
+        writeln!(s, "    <node id=\"n{id}\">").unwrap();
+        data(&mut s, "label", format!("{id}:{}", planet.name(lang)));
+        data(&mut s, "name", planet.name(Language::Japanese));
+        data(&mut s, "name_en", planet.name(Language::English));
+        data(&mut s, "x", pos.x);
+        data(&mut s, "y", pos.y);
+        data(&mut s, "z", pos.z);
+        data(&mut s, "volume", planet.volume().key());
+        data(&mut s, "map_x", mx);
+        data(&mut s, "map_y", my);
+        s.push_str("    </node>\n");
+    }
+    for (id1, id2) in map.edges() {
+        let (distance, turns) = edge_weights(map, rules, id1, id2, speed);
+        writeln!(s, "    <edge source=\"n{id1}\" target=\"n{id2}\">").unwrap();
+        data(&mut s, "distance", distance);
+        data(&mut s, "turns", turns);
+        s.push_str("    </edge>\n");
+    }
+    s.push_str("  </graph>\n</graphml>\n");
+
+    s
+}
+
+/// 隣接する惑星間の (距離, 実所要ターン数) を返す。
+fn edge_weights(
+    map: &StarMap,
+    rules: &dyn MovementRules,
+    id1: usize,
+    id2: usize,
+    speed: u32,
+) -> (u32, u32) {
+    let (p1, p2) = (map.planet(id1).position(), map.planet(id2).position());
+    let (turns, _) = rules.actual_turns_and_energy(p1, p2, speed);
+
+    (rules.distance(p1, p2), turns)
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut res = String::new();
+    for c in s.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&apos;"),
+            c => res.push(c),
+        }
+    }
+
+    res
+}
+
+/// 取り除くとグラフの連結成分が増える惑星 (関節点) を ID の昇順で返す。
+pub fn articulation_points(map: &StarMap) -> Vec<usize> {
+    let lowlink = Lowlink::new(map);
+
+    (0..map.planet_count())
+        .filter(|&id| lowlink.is_articulation_point(id))
+        .collect()
+}
+
+/// 取り除くとグラフの連結成分が増える隣接関係 (橋) を返す。
+/// 各要素は `(id1, id2)` (`id1 < id2`) で、昇順に並ぶ。
+pub fn bridges(map: &StarMap) -> Vec<(usize, usize)> {
+    let lowlink = Lowlink::new(map);
+
+    let mut bridges: Vec<_> = map
+        .edges()
+        .filter(|&(id1, id2)| lowlink.is_bridge(id1, id2))
+        .collect();
+    bridges.sort_unstable();
+
+    bridges
+}
+
+/// 始点の惑星から各惑星への最小ホップ数を返す。到達できない惑星は `None`。
+pub fn hop_distances(map: &StarMap, src: usize) -> Vec<Option<u32>> {
+    let (hops, _) = bfs(map, src);
+
+    hops
+}
+
+/// 始点から目的地へのホップ数最小の経路を返す。始点と目的地を含む。
+///
+/// ホップ数最小の経路が複数あれば、そのうち距離 (`StarMap::distance`) の合計が最小のものを返す。
+/// 到達できない場合は `None` を返す。
+pub fn hop_path(map: &StarMap, src: usize, dst: usize) -> Option<Vec<usize>> {
+    let n = map.planet_count();
+    let (hops, _) = bfs(map, src);
+    hops[dst]?;
+
+    // 最短経路 DAG 上で距離の合計が最小の経路を、ホップ数の小さい方から順に確定させる。
+    let mut order: Vec<_> = (0..n).filter(|&id| hops[id].is_some()).collect();
+    order.sort_by_key(|&id| hops[id]);
+    let mut best: Vec<Option<(u32, usize)>> = vec![None; n];
+    best[src] = Some((0, src));
+    for &id in &order {
+        let Some((total, _)) = best[id] else {
+            continue;
+        };
+        for &next in map.planet(id).neighbors() {
+            if hops[next] != hops[id].map(|h| h + 1) {
+                continue;
+            }
+            let candidate = total + map.distance(id, next);
+            if best[next].is_none_or(|(t, _)| candidate < t) {
+                best[next] = Some((candidate, id));
+            }
+        }
+    }
+
+    let mut path = vec![dst];
+    while let Some(&id) = path.last().filter(|&&id| id != src) {
+        path.push(best[id]?.1);
+    }
+    path.reverse();
+
+    Some(path)
+}
+
+/// 経路上の隣接する惑星間の距離の合計を返す。
+pub fn path_distance(map: &StarMap, path: &[usize]) -> u32 {
+    path.windows(2).map(|w| map.distance(w[0], w[1])).sum()
+}
+
+/// 各惑星の媒介中心性 (ホップ数最小の経路の上にある割合の総和) を返す。
+///
+/// 無向グラフとして、惑星の組 (順序を区別しない) ごとに数える。
+pub fn betweenness_centrality(map: &StarMap) -> Vec<f64> {
+    // Brandes のアルゴリズム。
+    let n = map.planet_count();
+    let mut centrality = vec![0.0; n];
+
+    for src in 0..n {
+        let (hops, order) = bfs(map, src);
+
+        // 始点から各惑星へのホップ数最小の経路の数。
+        let mut paths = vec![0.0; n];
+        paths[src] = 1.0;
+        for &id in &order {
+            for &next in map.planet(id).neighbors() {
+                if hops[next] == hops[id].map(|h| h + 1) {
+                    paths[next] += paths[id];
+                }
+            }
+        }
+
+        let mut dependency = vec![0.0; n];
+        for &id in order.iter().rev() {
+            for &next in map.planet(id).neighbors() {
+                if hops[next] == hops[id].map(|h| h + 1) {
+                    dependency[id] += paths[id] / paths[next] * (1.0 + dependency[next]);
+                }
+            }
+            if id != src {
+                centrality[id] += dependency[id];
+            }
+        }
+    }
+
+    // 各組を両方向から数えている。
+    centrality.iter().map(|c| c / 2.0).collect()
+}
+
+/// グラフの直径 (到達可能な惑星の組の最小ホップ数の最大値) と、それを与える惑星の組を返す。
+/// 該当する組が複数あれば ID の辞書順で最初のものを返す。惑星がなければ `None` を返す。
+pub fn diameter(map: &StarMap) -> Option<(u32, (usize, usize))> {
+    let n = map.planet_count();
+
+    let mut diameter: Option<(u32, (usize, usize))> = None;
+    for src in 0..n {
+        for (dst, hops) in hop_distances(map, src).into_iter().enumerate() {
+            let Some(hops) = hops else {
+                continue;
+            };
+            if diameter.is_none_or(|(d, _)| hops > d) {
+                diameter = Some((hops, (src, dst)));
+            }
+        }
+    }
+
+    diameter
+}
+
+/// 幅優先探索で (各惑星へのホップ数, 訪問順) を返す。
+fn bfs(map: &StarMap, src: usize) -> (Vec<Option<u32>>, Vec<usize>) {
+    let mut hops = vec![None; map.planet_count()];
+    let mut order = vec![];
+
+    hops[src] = Some(0);
+    let mut queue = VecDeque::from([src]);
+    while let Some(id) = queue.pop_front() {
+        order.push(id);
+        let next_hops = hops[id].map(|h| h + 1);
+        for &next in map.planet(id).neighbors() {
+            if hops[next].is_none() {
+                hops[next] = next_hops;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    (hops, order)
+}
+
+/// 深さ優先探索の訪問順 (`ord`) と、後退辺を 1 本まで使って到達できる最小の訪問順 (`low`)。
+#[derive(Debug)]
+struct Lowlink<'a> {
+    map: &'a StarMap,
+    ord: Vec<Option<usize>>,
+    low: Vec<usize>,
+    parent: Vec<Option<usize>>,
+    /// 深さ優先探索木での子の数。
+    children: Vec<usize>,
+    count: usize,
+}
+
+impl<'a> Lowlink<'a> {
+    fn new(map: &'a StarMap) -> Self {
+        let n = map.planet_count();
+        let mut lowlink = Self {
+            map,
+            ord: vec![None; n],
+            low: vec![0; n],
+            parent: vec![None; n],
+            children: vec![0; n],
+            count: 0,
+        };
+        for id in 0..n {
+            if lowlink.ord[id].is_none() {
+                lowlink.dfs(id);
+            }
+        }
+
+        lowlink
+    }
+
+    fn dfs(&mut self, id: usize) {
+        self.ord[id] = Some(self.count);
+        self.low[id] = self.count;
+        self.count += 1;
+
+        for &next in self.map.planet(id).neighbors() {
+            match self.ord[next] {
+                None => {
+                    self.parent[next] = Some(id);
+                    self.children[id] += 1;
+                    self.dfs(next);
+                    self.low[id] = self.low[id].min(self.low[next]);
+                }
+                Some(ord) if self.parent[id] != Some(next) => {
+                    self.low[id] = self.low[id].min(ord);
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    fn is_articulation_point(&self, id: usize) -> bool {
+        match self.parent[id] {
+            // 深さ優先探索木の根は子が 2 つ以上あれば関節点。
+            None => self.children[id] >= 2,
+            Some(_) => {
+                let ord = self.ord[id].unwrap();
+                self.map
+                    .planet(id)
+                    .neighbors()
+                    .iter()
+                    .any(|&child| self.parent[child] == Some(id) && self.low[child] >= ord)
+            }
+        }
+    }
+
+    fn is_bridge(&self, id1: usize, id2: usize) -> bool {
+        let (parent, child) = if self.parent[id2] == Some(id1) {
+            (id1, id2)
+        } else if self.parent[id1] == Some(id2) {
+            (id2, id1)
+        } else {
+            // 深さ優先探索木に含まれない辺は閉路の一部。
+            return false;
+        };
+
+        self.low[child] > self.ord[parent].unwrap()
+    }
+}
@@ -0,0 +1,154 @@
+//! IPS 形式のパッチの生成と適用。
+//!
+//! IPS は `PATCH` に続いてレコード (3 バイトのオフセット、2 バイトのサイズ、データ) を並べ、`EOF` で終わる。
+//! サイズが 0 のレコードは RLE で、2 バイトの繰り返し回数と 1 バイトの値が続く。
+
+use std::fmt;
+
+const HEADER: &[u8; 5] = b"PATCH";
+const FOOTER: &[u8; 3] = b"EOF";
+const MAX_OFFSET: usize = 0xff_ffff;
+const MAX_SIZE: usize = 0xffff;
+/// `EOF` と同じバイト列になるオフセット。このオフセットから始まるレコードは終端と区別できない。
+const EOF_OFFSET: usize = 0x45_4f46;
+
+/// ROM イメージの `offset` から `data` を書き込むレコード。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IpsRecord {
+    pub offset: usize,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IpsPatch {
+    records: Vec<IpsRecord>,
+}
+
+impl IpsPatch {
+    /// `original` を `modified` に変えるパッチを作る。
+    ///
+    /// IPS ではファイルを縮められないので、`modified` は `original` 以上の長さでなければならない。
+    pub fn diff(original: &[u8], modified: &[u8]) -> Result<Self, IpsError> {
+        if modified.len() < original.len() {
+            return Err(IpsError::Shrunk);
+        }
+
+        let changed = |i: usize| original.get(i) != Some(&modified[i]);
+        let mut records = vec![];
+        let mut i = 0;
+        while i < modified.len() {
+            if !changed(i) {
+                i += 1;
+                continue;
+            }
+
+            // 変更のないバイトを 1 つ含めて、レコードが `EOF_OFFSET` から始まらないようにする。
+            let start = if i == EOF_OFFSET { i - 1 } else { i };
+            let mut end = i + 1;
+            while end < modified.len() && end - start < MAX_SIZE && changed(end) {
+                end += 1;
+            }
+            if start > MAX_OFFSET {
+                return Err(IpsError::OffsetTooLarge(start));
+            }
+            records.push(IpsRecord {
+                offset: start,
+                data: modified[start..end].to_vec(),
+            });
+            i = end;
+        }
+
+        Ok(Self { records })
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, IpsError> {
+        let mut rest = bytes.strip_prefix(HEADER).ok_or(IpsError::NotIps)?;
+        let mut take = |n: usize| {
+            let (head, tail) = rest.split_at_checked(n).ok_or(IpsError::Truncated)?;
+            rest = tail;
+            Ok(head)
+        };
+        let be = |bytes: &[u8]| bytes.iter().fold(0, |n, &b| n << 8 | usize::from(b));
+
+        let mut records = vec![];
+        loop {
+            let offset = take(3)?;
+            if offset == FOOTER {
+                break;
+            }
+            let offset = be(offset);
+            let data = match be(take(2)?) {
+                0 => {
+                    let count = be(take(2)?);
+                    vec![take(1)?[0]; count]
+                }
+                size => take(size)?.to_vec(),
+            };
+            records.push(IpsRecord { offset, data });
+        }
+
+        Ok(Self { records })
+    }
+
+    pub fn records(&self) -> &[IpsRecord] {
+        &self.records
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// パッチをバイト列にする。RLE のレコードは作らない。
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = HEADER.to_vec();
+        for record in &self.records {
+            bytes.extend(&record.offset.to_be_bytes()[size_of::<usize>() - 3..]);
+            bytes.extend(&record.data.len().to_be_bytes()[size_of::<usize>() - 2..]);
+            bytes.extend(&record.data);
+        }
+        bytes.extend(FOOTER);
+
+        bytes
+    }
+
+    /// パッチを適用したものを返す。レコードが末尾を越える場合は伸ばす。
+    pub fn apply(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut bytes = bytes.to_vec();
+        for IpsRecord { offset, data } in &self.records {
+            let end = offset + data.len();
+            if bytes.len() < end {
+                bytes.resize(end, 0);
+            }
+            bytes[*offset..end].copy_from_slice(data);
+        }
+
+        bytes
+    }
+}
+
+#[derive(Debug)]
+pub enum IpsError {
+    /// `PATCH` で始まらない。
+    NotIps,
+    /// `EOF` の前に途切れている。
+    Truncated,
+    /// 変更後のほうが短い。
+    Shrunk,
+    /// 3 バイトで表せないオフセットに変更がある。
+    OffsetTooLarge(usize),
+}
+
+impl fmt::Display for IpsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotIps => write!(f, "IPS 形式のパッチではない"),
+            Self::Truncated => write!(f, "パッチが途中で切れている"),
+            Self::Shrunk => write!(f, "IPS ではファイルを縮められない"),
+            Self::OffsetTooLarge(offset) => {
+                write!(f, "IPS で表せないオフセットに変更がある: {offset:#x}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IpsError {}
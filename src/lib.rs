@@ -1,7 +1,18 @@
-mod geometry;
-mod planet;
-mod query;
-mod travel;
+pub mod export;
+pub mod geometry;
+pub mod graph;
+pub mod ips;
+pub mod locale;
+pub mod matrix;
+pub mod planet;
+pub mod planetset;
+pub mod query;
+pub mod rom;
+pub mod search;
+pub mod sqrtreport;
+pub mod starmap;
+pub mod travel;
+pub mod validate;
 mod web;
 
 pub use self::web::start;
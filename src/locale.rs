@@ -0,0 +1,35 @@
+/// 表示言語。
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Language {
+    #[default]
+    Japanese,
+    English,
+}
+
+impl Language {
+    pub fn iter() -> impl Iterator<Item = Self> + Clone {
+        use Language::*;
+
+        [Japanese, English].into_iter()
+    }
+
+    /// BCP 47 の言語タグを返す。
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Japanese => "ja",
+            Self::English => "en",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::iter().find(|lang| lang.code() == code)
+    }
+
+    /// その言語自身での言語名を返す。
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Self::Japanese => "日本語",
+            Self::English => "English",
+        }
+    }
+}
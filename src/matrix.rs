@@ -0,0 +1,120 @@
+use serde::Serialize;
+
+use crate::export::{Format, Table};
+use crate::locale::Language;
+use crate::planetset::PlanetSet;
+use crate::starmap::StarMap;
+use crate::travel::MovementRules;
+
+/// 全惑星間の (実所要ターン数, 実所要エネルギー) の表。
+///
+/// 行が始点、列が目的地。与えたエネルギーで移動命令を出せるかどうかも併せて持つ。
+#[derive(Debug)]
+pub struct TravelMatrix<'a> {
+    map: &'a StarMap,
+    speed: u32,
+    energy: u32,
+    /// `costs[src * 惑星数 + dst]`
+    costs: Vec<(u32, u32)>,
+    reachables: Vec<PlanetSet>,
+}
+
+impl<'a> TravelMatrix<'a> {
+    pub fn new(map: &'a StarMap, rules: &dyn MovementRules, speed: u32, energy: u32) -> Self {
+        let n = map.planet_count();
+        let position = |id| map.planet(id).position();
+        let costs = itertools::iproduct!(0..n, 0..n)
+            .map(|(src, dst)| rules.actual_turns_and_energy(position(src), position(dst), speed))
+            .collect();
+        let reachables = (0..n)
+            .map(|src| rules.reachable_planets(map, src, energy))
+            .collect();
+
+        Self {
+            map,
+            speed,
+            energy,
+            costs,
+            reachables,
+        }
+    }
+
+    /// 始点と目的地の惑星を与えたときの (実所要ターン数, 実所要エネルギー) を返す。
+    /// 到達可能かどうかに関わらず値を返す。
+    pub fn cost(&self, src: usize, dst: usize) -> (u32, u32) {
+        self.costs[src * self.map.planet_count() + dst]
+    }
+
+    pub fn is_reachable(&self, src: usize, dst: usize) -> bool {
+        self.reachables[src].contains(dst)
+    }
+
+    /// 始点と目的地の 1 組を 1 行とする表を返す。惑星名は `lang` の名前とする。
+    pub fn to_table(&self, lang: Language) -> Table {
+        let mut table = Table::new(&[
+            "src_id",
+            "src_name",
+            "dst_id",
+            "dst_name",
+            "turns",
+            "energy",
+            "reachable",
+        ]);
+
+        let name = |id| self.map.planet(id).name(lang);
+        let n = self.map.planet_count();
+        for src in 0..n {
+            for dst in 0..n {
+                let (turns, energy) = self.cost(src, dst);
+                table.push_row(vec![
+                    (src as u32).into(),
+                    name(src).into(),
+                    (dst as u32).into(),
+                    name(dst).into(),
+                    turns.into(),
+                    energy.into(),
+                    self.is_reachable(src, dst).into(),
+                ]);
+            }
+        }
+
+        table
+    }
+
+    /// 1 行 1 組の CSV 形式で出力する。
+    pub fn to_csv(&self, lang: Language) -> String {
+        self.to_table(lang).to_string(Format::Csv)
+    }
+
+    /// 行列形式の JSON で出力する。
+    pub fn to_json(&self, lang: Language) -> String {
+        #[derive(Serialize)]
+        struct Json<'a> {
+            speed: u32,
+            energy: u32,
+            planets: Vec<&'a str>,
+            turns: Vec<Vec<u32>>,
+            energies: Vec<Vec<u32>>,
+            reachable: Vec<Vec<bool>>,
+        }
+
+        fn matrix<T>(n: usize, f: impl Fn(usize, usize) -> T) -> Vec<Vec<T>> {
+            (0..n)
+                .map(|src| (0..n).map(|dst| f(src, dst)).collect())
+                .collect()
+        }
+
+        let n = self.map.planet_count();
+
+        let json = Json {
+            speed: self.speed,
+            energy: self.energy,
+            planets: (0..n).map(|id| self.map.planet(id).name(lang)).collect(),
+            turns: matrix(n, |src, dst| self.cost(src, dst).0),
+            energies: matrix(n, |src, dst| self.cost(src, dst).1),
+            reachable: matrix(n, |src, dst| self.is_reachable(src, dst)),
+        };
+
+        serde_json::to_string(&json).unwrap()
+    }
+}
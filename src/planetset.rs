@@ -0,0 +1,82 @@
+/// 惑星 ID の集合 (ビット集合)。
+///
+/// 先頭 64 惑星分はヒープを使わずに持つので、組み込みのマップ程度の惑星数なら固定長配列と同様に扱える。
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PlanetSet {
+    universe_len: usize,
+    head: u64,
+    tail: Vec<u64>,
+}
+
+impl PlanetSet {
+    /// 惑星数 `universe_len` のマップについての空集合を返す。
+    pub fn new(universe_len: usize) -> Self {
+        Self {
+            universe_len,
+            head: 0,
+            tail: vec![0; universe_len.saturating_sub(64).div_ceil(64)],
+        }
+    }
+
+    /// 惑星数 `universe_len` のマップについての全惑星の集合を返す。
+    pub fn full(universe_len: usize) -> Self {
+        let mut set = Self::new(universe_len);
+        for id in 0..universe_len {
+            set.insert(id);
+        }
+
+        set
+    }
+
+    /// 対象とするマップの惑星数を返す。
+    pub fn universe_len(&self) -> usize {
+        self.universe_len
+    }
+
+    /// 含まれる惑星の数を返す。
+    pub fn len(&self) -> usize {
+        let tail: u32 = self.tail.iter().map(|word| word.count_ones()).sum();
+        (self.head.count_ones() + tail) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head == 0 && self.tail.iter().all(|&word| word == 0)
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        assert!(id < self.universe_len, "惑星 ID が範囲外: {id}");
+        let (word, bit) = self.word(id);
+
+        word & bit != 0
+    }
+
+    /// 惑星を追加する。既に含まれていたら `false` を返す。
+    pub fn insert(&mut self, id: usize) -> bool {
+        assert!(id < self.universe_len, "惑星 ID が範囲外: {id}");
+        let word = if id < 64 {
+            &mut self.head
+        } else {
+            &mut self.tail[id / 64 - 1]
+        };
+        let bit = 1 << (id % 64);
+        let added = *word & bit == 0;
+        *word |= bit;
+
+        added
+    }
+
+    /// 含まれる惑星の ID を昇順に返す。
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.universe_len).filter(|&id| self.contains(id))
+    }
+
+    fn word(&self, id: usize) -> (u64, u64) {
+        let word = if id < 64 {
+            self.head
+        } else {
+            self.tail[id / 64 - 1]
+        };
+
+        (word, 1 << (id % 64))
+    }
+}
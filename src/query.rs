@@ -1,23 +1,76 @@
+use crate::export::{Format, Table};
 use crate::geometry::Vec3;
-use crate::planet::{planet_position, PLANET_COUNT};
-use crate::travel;
+use crate::locale::Language;
+use crate::planetset::PlanetSet;
+use crate::starmap::StarMap;
+use crate::travel::MovementRules;
 
 #[derive(Debug)]
-pub struct Answer {
+pub struct Answer<'a> {
+    map: &'a StarMap,
+    rules: &'a dyn MovementRules,
     src: Vec3,
-    reachables: [bool; PLANET_COUNT],
+    src_planet: Option<usize>,
+    reachables: PlanetSet,
+    edges: Vec<(usize, usize)>,
 }
 
-impl Answer {
+impl Answer<'_> {
+    /// 始点の惑星を返す。始点が座標指定なら `None` を返す。
+    pub fn src_planet(&self) -> Option<usize> {
+        self.src_planet
+    }
+
     pub fn is_reachable(&self, id: usize) -> bool {
-        self.reachables[id]
+        self.reachables.contains(id)
+    }
+
+    /// 移動命令を出せる惑星の探索で辿った隣接関係 (向きは問わない) かどうかを返す。
+    /// 始点が座標指定なら常に `false` を返す。
+    pub fn is_edge_searched(&self, id1: usize, id2: usize) -> bool {
+        self.edges.contains(&(id1, id2)) || self.edges.contains(&(id2, id1))
     }
 
     /// 目的地の惑星および移動力を与えたときの (実所要ターン数, 実所要エネルギー) を返す。
     /// 到達不能なら `None` を返す。
     pub fn cost(&self, id: usize, speed: u32) -> Option<(u32, u32)> {
-        self.reachables[id]
-            .then(|| travel::actual_turns_and_energy(self.src, planet_position(id), speed))
+        self.reachables.contains(id).then(|| {
+            self.rules
+                .actual_turns_and_energy(self.src, self.map.planet(id).position(), speed)
+        })
+    }
+
+    /// 始点を除く各目的地について (目的地, 距離, 日数, 消費, 到達可能か) を並べた表を返す。
+    /// 到達不能な目的地の日数と消費は空欄とする。惑星名は `lang` の名前とする。
+    pub fn to_table(&self, speed: u32, lang: Language) -> Table {
+        let mut table = Table::new(&[
+            "dst_id",
+            "dst_name",
+            "distance",
+            "turns",
+            "energy",
+            "reachable",
+        ]);
+
+        for dst in (0..self.map.planet_count()).filter(|&dst| Some(dst) != self.src_planet) {
+            let planet = self.map.planet(dst);
+            let cost = self.cost(dst, speed);
+            table.push_row(vec![
+                (dst as u32).into(),
+                planet.name(lang).into(),
+                self.rules.distance(self.src, planet.position()).into(),
+                cost.map(|(turns, _)| turns).into(),
+                cost.map(|(_, energy)| energy).into(),
+                self.is_reachable(dst).into(),
+            ]);
+        }
+
+        table
+    }
+
+    /// 移動力を与えたときの結果を指定した形式の文字列として返す。
+    pub fn export(&self, speed: u32, format: Format, lang: Language) -> String {
+        self.to_table(speed, lang).to_string(format)
     }
 }
 
@@ -32,30 +85,42 @@ impl Query {
         Self::Planet(QueryPlanet::new(src, energy))
     }
 
+    pub fn new_position(src: Vec3) -> Self {
+        Self::Position(QueryPosition::new(src))
+    }
+
     pub fn default_position() -> Self {
         Self::Position(Default::default())
     }
 
-    pub fn src_pos(&self) -> Vec3 {
+    pub fn src_pos(&self, map: &StarMap) -> Vec3 {
         match self {
-            Self::Planet(q) => planet_position(q.src()),
+            Self::Planet(q) => map.planet(q.src()).position(),
             Self::Position(q) => q.src(),
         }
     }
 
-    pub fn execute(&self) -> Answer {
-        let (src, reachables) = match self {
+    pub fn execute<'a>(&self, map: &'a StarMap, rules: &'a dyn MovementRules) -> Answer<'a> {
+        let (src, src_planet, reachables, edges) = match self {
             Self::Planet(q) => {
-                let reachables = travel::reachable_planets(q.src(), q.energy());
-                (planet_position(q.src()), reachables)
+                let (reachables, edges) =
+                    rules.reachable_planets_and_edges(map, q.src(), q.energy());
+                (self.src_pos(map), Some(q.src()), reachables, edges)
             }
             Self::Position(q) => {
-                let reachables = [true; PLANET_COUNT];
-                (q.src(), reachables)
+                let reachables = PlanetSet::full(map.planet_count());
+                (q.src(), None, reachables, vec![])
             }
         };
 
-        Answer { src, reachables }
+        Answer {
+            map,
+            rules,
+            src,
+            src_planet,
+            reachables,
+            edges,
+        }
     }
 }
 
@@ -106,6 +171,10 @@ impl Default for QueryPlanet {
 pub struct QueryPosition(Vec3);
 
 impl QueryPosition {
+    pub fn new(src: Vec3) -> Self {
+        Self(src)
+    }
+
     pub fn src(&self) -> Vec3 {
         self.0
     }
@@ -0,0 +1,421 @@
+//! iNES 形式の ROM イメージからのマップの表の抽出と書き込み、組み込みのマップとの比較。
+//!
+//! 表の位置や文字コードはカートリッジの版ごとに異なりうるので、呼び出し側が [`RomLayout`] で与える。
+//! 実際のカートリッジの配置は調べられておらず、このクレートには含まれていないので、
+//! `logh-rom` や `logh-ips` に渡す配置の JSON は利用者が用意する。
+//! `tests/fixtures/rom_layout.json` はテスト用の合成 ROM の配置で、実際の ROM には使えない。
+
+use std::fmt;
+use std::ops::Range;
+
+use serde::Deserialize;
+
+use crate::geometry::Vec3;
+use crate::locale::Language;
+use crate::starmap::StarMap;
+
+const INES_MAGIC: &[u8; 4] = b"NES\x1a";
+const INES_HEADER_SIZE: usize = 16;
+const TRAINER_SIZE: usize = 512;
+const PRG_BANK_SIZE: usize = 16 * 1024;
+const CHR_BANK_SIZE: usize = 8 * 1024;
+
+/// iNES 形式の ROM イメージ。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct INesRom {
+    mapper: u8,
+    prg_offset: usize,
+    prg: Vec<u8>,
+    chr: Vec<u8>,
+}
+
+impl INesRom {
+    pub fn parse(bytes: &[u8]) -> Result<Self, RomError> {
+        let header = bytes.get(..INES_HEADER_SIZE).ok_or(RomError::NotINes)?;
+        if &header[..4] != INES_MAGIC {
+            return Err(RomError::NotINes);
+        }
+
+        let prg_size = usize::from(header[4]) * PRG_BANK_SIZE;
+        let chr_size = usize::from(header[5]) * CHR_BANK_SIZE;
+        let has_trainer = header[6] & 0x04 != 0;
+        let mapper = (header[6] >> 4) | (header[7] & 0xf0);
+
+        let prg_start = INES_HEADER_SIZE + if has_trainer { TRAINER_SIZE } else { 0 };
+        let chr_start = prg_start + prg_size;
+        let prg = bytes
+            .get(prg_start..chr_start)
+            .ok_or(RomError::Truncated("PRG ROM"))?;
+        let chr = bytes
+            .get(chr_start..chr_start + chr_size)
+            .ok_or(RomError::Truncated("CHR ROM"))?;
+
+        Ok(Self {
+            mapper,
+            prg_offset: prg_start,
+            prg: prg.to_vec(),
+            chr: chr.to_vec(),
+        })
+    }
+
+    pub fn mapper(&self) -> u8 {
+        self.mapper
+    }
+
+    /// ROM イメージの先頭から PRG ROM の先頭までのバイト数。
+    pub fn prg_offset(&self) -> usize {
+        self.prg_offset
+    }
+
+    pub fn prg(&self) -> &[u8] {
+        &self.prg
+    }
+
+    pub fn chr(&self) -> &[u8] {
+        &self.chr
+    }
+}
+
+/// ROM 内のマップの表の配置。オフセットは PRG ROM の先頭からのバイト数。
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct RomLayout {
+    pub planet_count: usize,
+    /// 各惑星の x, y, z を 1 バイトずつ並べた表。
+    pub positions: TableLayout,
+    /// 各惑星の隣接惑星の ID を並べた表。
+    pub neighbors: TableLayout,
+    /// 各惑星の名前の表。省略すると名前は抽出しない。
+    #[serde(default)]
+    pub names: Option<NameLayout>,
+}
+
+impl RomLayout {
+    pub fn from_json(s: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+}
+
+/// 惑星ごとに固定長の要素を並べた表の配置。
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct TableLayout {
+    pub offset: usize,
+    /// 1 惑星分の要素のバイト数。
+    pub stride: usize,
+    /// 可変長の要素の終端を表すバイト。省略すると要素は `stride` バイト全体。
+    #[serde(default)]
+    pub terminator: Option<u8>,
+}
+
+impl TableLayout {
+    /// 惑星 `id` の要素の PRG ROM 内の範囲。`usize` で表せなければ `None` を返す。
+    fn entry_range(&self, id: usize) -> Option<Range<usize>> {
+        let start = id
+            .checked_mul(self.stride)
+            .and_then(|n| n.checked_add(self.offset))?;
+
+        Some(start..start.checked_add(self.stride)?)
+    }
+}
+
+/// 惑星名の表の配置と文字コード。
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct NameLayout {
+    #[serde(flatten)]
+    pub table: TableLayout,
+    /// 名前の言語 (BCP 47 の言語タグ)。マップと比較する名前の言語を決める。
+    pub language: String,
+    /// バイト値を添字とする文字の表。
+    pub charset: Vec<String>,
+}
+
+/// ROM から抽出した惑星のデータ。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RomPlanet {
+    pub position: Vec3,
+    pub neighbors: Vec<usize>,
+    pub name: Option<String>,
+}
+
+/// ROM からマップの表を抽出する。
+pub fn extract(rom: &INesRom, layout: &RomLayout) -> Result<Vec<RomPlanet>, RomError> {
+    let table = |name: &'static str, table: &TableLayout, id: usize| {
+        let entry = table
+            .entry_range(id)
+            .and_then(|range| rom.prg().get(range))
+            .ok_or(RomError::Truncated(name))?;
+        let len = table
+            .terminator
+            .and_then(|t| entry.iter().position(|&b| b == t))
+            .unwrap_or(entry.len());
+
+        Ok(&entry[..len])
+    };
+
+    if let Some(names) = &layout.names {
+        if Language::from_code(&names.language).is_none() {
+            return Err(RomError::UnknownLanguage(names.language.clone()));
+        }
+    }
+
+    (0..layout.planet_count)
+        .map(|id| {
+            let position = match *table("positions", &layout.positions, id)? {
+                [x, y, z, ..] => Vec3::new(x.into(), y.into(), z.into()),
+                _ => return Err(RomError::Truncated("positions")),
+            };
+
+            let neighbors = table("neighbors", &layout.neighbors, id)?
+                .iter()
+                .map(|&b| usize::from(b))
+                .collect();
+
+            let name = match &layout.names {
+                Some(names) => {
+                    let bytes = table("names", &names.table, id)?;
+                    let chars = bytes.iter().map(|&b| {
+                        names
+                            .charset
+                            .get(usize::from(b))
+                            .map(String::as_str)
+                            .ok_or(RomError::UnknownCharacter { id, byte: b })
+                    });
+                    Some(chars.collect::<Result<String, _>>()?)
+                }
+                None => None,
+            };
+
+            Ok(RomPlanet {
+                position,
+                neighbors,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// マップの座標と隣接惑星の表を ROM イメージに書き込んだものを返す。
+///
+/// 名前の表は書き込まない。可変長の要素の終端より後ろのバイトは元のまま残す。
+pub fn write_tables(bytes: &[u8], layout: &RomLayout, map: &StarMap) -> Result<Vec<u8>, RomError> {
+    if map.planet_count() != layout.planet_count {
+        return Err(RomError::PlanetCountMismatch {
+            layout: layout.planet_count,
+            map: map.planet_count(),
+        });
+    }
+
+    let rom = INesRom::parse(bytes)?;
+    let mut bytes = bytes.to_vec();
+    let mut write = |name: &'static str, table: &TableLayout, id: usize, data: &[u8]| {
+        let range = table
+            .entry_range(id)
+            .filter(|range| range.end <= rom.prg().len())
+            .ok_or(RomError::Truncated(name))?;
+        let start = rom.prg_offset() + range.start;
+        bytes[start..start + data.len()].copy_from_slice(data);
+
+        Ok(())
+    };
+
+    for (id, planet) in map.planets().iter().enumerate() {
+        let Vec3 { x, y, z } = planet.position();
+        let position = [x, y, z].map(u8::try_from);
+        let position = match position {
+            [Ok(x), Ok(y), Ok(z)] if layout.positions.stride >= 3 => [x, y, z],
+            _ => return Err(RomError::PositionDoesNotFit { id }),
+        };
+        write("positions", &layout.positions, id, &position)?;
+
+        let mut neighbors = planet
+            .neighbors()
+            .iter()
+            .map(|&id| u8::try_from(id))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| RomError::NeighborsDoNotFit { id })?;
+        // 終端と同じ ID を書き込むと、抽出時にそこで要素が終わってしまう。
+        if let Some(&neighbor) = layout
+            .neighbors
+            .terminator
+            .and_then(|t| neighbors.iter().find(|&&b| b == t))
+        {
+            return Err(RomError::NeighborIsTerminator {
+                id,
+                neighbor: neighbor.into(),
+            });
+        }
+        neighbors.extend(layout.neighbors.terminator);
+        // 終端がなければ要素は `stride` バイト全体なので、隣接惑星の数はちょうど `stride` でなければならない。
+        let fits = match layout.neighbors.terminator {
+            Some(_) => neighbors.len() <= layout.neighbors.stride,
+            None => neighbors.len() == layout.neighbors.stride,
+        };
+        if !fits {
+            return Err(RomError::NeighborsDoNotFit { id });
+        }
+        write("neighbors", &layout.neighbors, id, &neighbors)?;
+    }
+
+    Ok(bytes)
+}
+
+/// ROM から抽出した表とマップの相違。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TableDiff {
+    PlanetCount {
+        rom: usize,
+        map: usize,
+    },
+    Position {
+        id: usize,
+        rom: Vec3,
+        map: Vec3,
+    },
+    /// 隣接惑星は順序を問わずに比較する。
+    Neighbors {
+        id: usize,
+        rom: Vec<usize>,
+        map: Vec<usize>,
+    },
+    Name {
+        id: usize,
+        rom: String,
+        map: String,
+    },
+}
+
+impl fmt::Display for TableDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn xyz(v: &Vec3) -> String {
+            format!("({}, {}, {})", v.x, v.y, v.z)
+        }
+
+        match self {
+            Self::PlanetCount { rom, map } => {
+                write!(f, "惑星数: ROM {rom}, マップ {map}")
+            }
+            Self::Position { id, rom, map } => {
+                write!(f, "惑星 {id} の座標: ROM {}, マップ {}", xyz(rom), xyz(map))
+            }
+            Self::Neighbors { id, rom, map } => {
+                write!(f, "惑星 {id} の隣接惑星: ROM {rom:?}, マップ {map:?}")
+            }
+            Self::Name { id, rom, map } => {
+                write!(f, "惑星 {id} の名前: ROM {rom:?}, マップ {map:?}")
+            }
+        }
+    }
+}
+
+/// ROM から抽出した表とマップを比較し、全ての相違を返す。
+///
+/// 名前は ROM から抽出した場合のみ、`language` の名前と比較する。
+/// 惑星数が異なる場合は、共通する ID の範囲の惑星を比較する。
+pub fn diff(planets: &[RomPlanet], map: &StarMap, language: Language) -> Vec<TableDiff> {
+    let mut diffs = vec![];
+
+    if planets.len() != map.planet_count() {
+        diffs.push(TableDiff::PlanetCount {
+            rom: planets.len(),
+            map: map.planet_count(),
+        });
+    }
+
+    for (id, (rom, planet)) in planets.iter().zip(map.planets()).enumerate() {
+        if rom.position != planet.position() {
+            diffs.push(TableDiff::Position {
+                id,
+                rom: rom.position,
+                map: planet.position(),
+            });
+        }
+
+        let mut rom_neighbors = rom.neighbors.clone();
+        rom_neighbors.sort_unstable();
+        let mut map_neighbors = planet.neighbors().to_vec();
+        map_neighbors.sort_unstable();
+        if rom_neighbors != map_neighbors {
+            diffs.push(TableDiff::Neighbors {
+                id,
+                rom: rom_neighbors,
+                map: map_neighbors,
+            });
+        }
+
+        if let Some(name) = &rom.name {
+            if name != planet.name(language) {
+                diffs.push(TableDiff::Name {
+                    id,
+                    rom: name.clone(),
+                    map: planet.name(language).to_owned(),
+                });
+            }
+        }
+    }
+
+    diffs
+}
+
+#[derive(Debug)]
+pub enum RomError {
+    /// iNES のヘッダがない。
+    NotINes,
+    /// ROM イメージや表が途中で切れている。
+    Truncated(&'static str),
+    UnknownLanguage(String),
+    /// 文字の表にないバイトが名前に含まれる。
+    UnknownCharacter {
+        id: usize,
+        byte: u8,
+    },
+    /// 書き込むマップの惑星数が配置と異なる。
+    PlanetCountMismatch {
+        layout: usize,
+        map: usize,
+    },
+    /// 座標が 1 バイトに収まらない。
+    PositionDoesNotFit {
+        id: usize,
+    },
+    /// 隣接惑星の ID が 1 バイトに収まらないか、隣接惑星の数が表の要素に収まらない。
+    NeighborsDoNotFit {
+        id: usize,
+    },
+    /// 隣接惑星の ID が隣接惑星の表の終端のバイトと等しい。
+    NeighborIsTerminator {
+        id: usize,
+        neighbor: usize,
+    },
+}
+
+impl fmt::Display for RomError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotINes => write!(f, "iNES 形式の ROM イメージではない"),
+            Self::Truncated(what) => write!(f, "{what} が ROM イメージの範囲外"),
+            Self::UnknownLanguage(code) => write!(f, "不明な言語: {code}"),
+            Self::UnknownCharacter { id, byte } => {
+                write!(
+                    f,
+                    "惑星 {id} の名前に文字の表にないバイトがある: {byte:#04x}"
+                )
+            }
+            Self::PlanetCountMismatch { layout, map } => {
+                write!(f, "惑星数が配置と異なる: 配置 {layout}, マップ {map}")
+            }
+            Self::PositionDoesNotFit { id } => {
+                write!(f, "惑星 {id} の座標が表に収まらない")
+            }
+            Self::NeighborsDoNotFit { id } => {
+                write!(f, "惑星 {id} の隣接惑星が表に収まらない")
+            }
+            Self::NeighborIsTerminator { id, neighbor } => {
+                write!(
+                    f,
+                    "惑星 {id} の隣接惑星 {neighbor} の ID が表の終端と等しい"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RomError {}
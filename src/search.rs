@@ -0,0 +1,243 @@
+use crate::locale::Language;
+use crate::starmap::StarMap;
+
+/// 惑星名の一致の種類。良い一致ほど小さい。
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum MatchKind {
+    Exact,
+    Prefix,
+    Substring,
+    /// 文字が飛び飛びに順番通り現れる。短すぎる検索語では判定しない。
+    Subsequence,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PlanetMatch {
+    pub id: usize,
+    pub kind: MatchKind,
+}
+
+/// 惑星を名前または ID で検索し、良い一致の順 (同順位なら ID 順) に返す。
+///
+/// 日本語名と英語名の両方と照合する。ひらがな/カタカナ、半角/全角、大文字/小文字の違いと
+/// 中黒・長音記号・空白の有無は区別しない。また、ローマ字の入力はかなに変換しても照合する。
+pub fn search_planets(map: &StarMap, query: &str) -> Vec<PlanetMatch> {
+    let query = normalize(query);
+    if query.is_empty() {
+        return vec![];
+    }
+
+    let mut queries = vec![query.clone()];
+    if let Some(kana) = romaji_to_hiragana(&query) {
+        queries.push(kana);
+    }
+
+    let mut matches: Vec<_> = (0..map.planet_count())
+        .filter_map(|id| {
+            let planet = map.planet(id);
+            let names = [
+                normalize(planet.name(Language::Japanese)),
+                normalize(planet.name(Language::English)),
+                id.to_string(),
+            ];
+            itertools::iproduct!(&names, &queries)
+                .filter_map(|(name, query)| match_kind(name, query))
+                .min()
+                .map(|kind| PlanetMatch { id, kind })
+        })
+        .collect();
+    matches.sort_by_key(|m| (m.kind, m.id));
+
+    matches
+}
+
+fn match_kind(name: &str, query: &str) -> Option<MatchKind> {
+    if name == query {
+        Some(MatchKind::Exact)
+    } else if name.starts_with(query) {
+        Some(MatchKind::Prefix)
+    } else if name.contains(query) {
+        Some(MatchKind::Substring)
+    } else if query.chars().count() >= 3 {
+        let mut chars = name.chars();
+        query
+            .chars()
+            .all(|c| chars.any(|d| d == c))
+            .then_some(MatchKind::Subsequence)
+    } else {
+        None
+    }
+}
+
+/// 照合用に文字列を正規化する。
+///
+/// 全角英数字は半角に、半角カタカナは全角に直した上で、カタカナをひらがなに、英字を小文字にする。
+/// 中黒・長音記号・空白・ハイフンは取り除く。
+fn normalize(s: &str) -> String {
+    let mut res = String::new();
+
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut c = fullwidth_to_ascii(c);
+        if let Some(kana) = halfwidth_to_katakana(c) {
+            c = kana;
+        }
+
+        // 後続の濁点・半濁点 (半角または結合文字) を合成する。
+        if let Some(&mark) = chars.peek() {
+            if let Some(voiced) = compose_voiced(c, mark) {
+                c = voiced;
+                chars.next();
+            }
+        }
+
+        if matches!(c, '・' | 'ー' | '-' | ' ' | '\u{3000}') {
+            continue;
+        }
+
+        res.extend(katakana_to_hiragana(c).to_lowercase());
+    }
+
+    res
+}
+
+fn fullwidth_to_ascii(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(u32::from(c) - 0xFEE0).unwrap(),
+        _ => c,
+    }
+}
+
+fn halfwidth_to_katakana(c: char) -> Option<char> {
+    const TABLE: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
+
+    match c {
+        '\u{FF61}'..='\u{FF9D}' => TABLE.chars().nth((u32::from(c) - 0xFF61) as usize),
+        _ => None,
+    }
+}
+
+/// カタカナと濁点・半濁点の組を 1 文字に合成する。
+fn compose_voiced(c: char, mark: char) -> Option<char> {
+    let dakuten = matches!(mark, '\u{FF9E}' | '\u{3099}' | '゛');
+    let handakuten = matches!(mark, '\u{FF9F}' | '\u{309A}' | '゜');
+
+    let offset = match c {
+        'ウ' if dakuten => return Some('ヴ'),
+        'カ' | 'キ' | 'ク' | 'ケ' | 'コ' | 'サ' | 'シ' | 'ス' | 'セ' | 'ソ' | 'タ' | 'チ'
+        | 'ツ' | 'テ' | 'ト'
+            if dakuten =>
+        {
+            1
+        }
+        'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' if dakuten => 1,
+        'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' if handakuten => 2,
+        _ => return None,
+    };
+
+    char::from_u32(u32::from(c) + offset)
+}
+
+fn katakana_to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => char::from_u32(u32::from(c) - 0x60).unwrap(),
+        _ => c,
+    }
+}
+
+/// ローマ字 (ヘボン式および訓令式) をひらがなに変換する。
+/// 英小文字以外を含む場合や、かなにならない綴りが残る場合は `None` を返す。
+fn romaji_to_hiragana(s: &str) -> Option<String> {
+    #[rustfmt::skip]
+    const TABLE: &[(&str, &str)] = &[
+        ("a", "あ"), ("i", "い"), ("u", "う"), ("e", "え"), ("o", "お"),
+        ("ka", "か"), ("ki", "き"), ("ku", "く"), ("ke", "け"), ("ko", "こ"),
+        ("sa", "さ"), ("si", "し"), ("shi", "し"), ("su", "す"), ("se", "せ"), ("so", "そ"),
+        ("ta", "た"), ("ti", "ち"), ("chi", "ち"), ("tu", "つ"), ("tsu", "つ"), ("te", "て"), ("to", "と"),
+        ("na", "な"), ("ni", "に"), ("nu", "ぬ"), ("ne", "ね"), ("no", "の"),
+        ("ha", "は"), ("hi", "ひ"), ("hu", "ふ"), ("fu", "ふ"), ("he", "へ"), ("ho", "ほ"),
+        ("ma", "ま"), ("mi", "み"), ("mu", "む"), ("me", "め"), ("mo", "も"),
+        ("ya", "や"), ("yu", "ゆ"), ("yo", "よ"),
+        ("ra", "ら"), ("ri", "り"), ("ru", "る"), ("re", "れ"), ("ro", "ろ"),
+        ("la", "ら"), ("li", "り"), ("lu", "る"), ("le", "れ"), ("lo", "ろ"),
+        ("wa", "わ"), ("wo", "を"),
+        ("ga", "が"), ("gi", "ぎ"), ("gu", "ぐ"), ("ge", "げ"), ("go", "ご"),
+        ("za", "ざ"), ("zi", "じ"), ("ji", "じ"), ("zu", "ず"), ("ze", "ぜ"), ("zo", "ぞ"),
+        ("da", "だ"), ("di", "ぢ"), ("du", "づ"), ("de", "で"), ("do", "ど"),
+        ("ba", "ば"), ("bi", "び"), ("bu", "ぶ"), ("be", "べ"), ("bo", "ぼ"),
+        ("pa", "ぱ"), ("pi", "ぴ"), ("pu", "ぷ"), ("pe", "ぺ"), ("po", "ぽ"),
+        ("va", "ゔぁ"), ("vi", "ゔぃ"), ("vu", "ゔ"), ("ve", "ゔぇ"), ("vo", "ゔぉ"),
+        ("fa", "ふぁ"), ("fi", "ふぃ"), ("fe", "ふぇ"), ("fo", "ふぉ"),
+        ("thi", "てぃ"), ("dhi", "でぃ"), ("twu", "とぅ"), ("dwu", "どぅ"),
+        ("kya", "きゃ"), ("kyu", "きゅ"), ("kyo", "きょ"),
+        ("sya", "しゃ"), ("syu", "しゅ"), ("syo", "しょ"),
+        ("sha", "しゃ"), ("shu", "しゅ"), ("sho", "しょ"), ("she", "しぇ"),
+        ("tya", "ちゃ"), ("tyu", "ちゅ"), ("tyo", "ちょ"),
+        ("cha", "ちゃ"), ("chu", "ちゅ"), ("cho", "ちょ"), ("che", "ちぇ"),
+        ("nya", "にゃ"), ("nyu", "にゅ"), ("nyo", "にょ"),
+        ("hya", "ひゃ"), ("hyu", "ひゅ"), ("hyo", "ひょ"),
+        ("mya", "みゃ"), ("myu", "みゅ"), ("myo", "みょ"),
+        ("rya", "りゃ"), ("ryu", "りゅ"), ("ryo", "りょ"),
+        ("gya", "ぎゃ"), ("gyu", "ぎゅ"), ("gyo", "ぎょ"),
+        ("zya", "じゃ"), ("zyu", "じゅ"), ("zyo", "じょ"),
+        ("ja", "じゃ"), ("ju", "じゅ"), ("jo", "じょ"), ("je", "じぇ"),
+        ("bya", "びゃ"), ("byu", "びゅ"), ("byo", "びょ"),
+        ("pya", "ぴゃ"), ("pyu", "ぴゅ"), ("pyo", "ぴょ"),
+        ("xa", "ぁ"), ("xi", "ぃ"), ("xu", "ぅ"), ("xe", "ぇ"), ("xo", "ぉ"),
+        ("xya", "ゃ"), ("xyu", "ゅ"), ("xyo", "ょ"), ("xtu", "っ"),
+        ("nn", "ん"),
+    ];
+
+    const VOWELS: &[u8] = b"aiueo";
+
+    let s = s.as_bytes();
+    if !s.iter().all(u8::is_ascii_lowercase) {
+        return None;
+    }
+
+    let mut res = String::new();
+    let mut i = 0;
+    while i < s.len() {
+        // 同じ子音の連続は促音とする ("nn" を除く)。
+        if i + 1 < s.len() && s[i] == s[i + 1] && s[i] != b'n' && !VOWELS.contains(&s[i]) {
+            res.push('っ');
+            i += 1;
+            continue;
+        }
+
+        // 母音・"y" の前の "nn" は撥音と "n" とする ("konnichi" は "こんにち")。
+        if s[i..].starts_with(b"nn")
+            && s.get(i + 2)
+                .is_some_and(|c| VOWELS.contains(c) || *c == b'y')
+        {
+            res.push('ん');
+            i += 1;
+            continue;
+        }
+
+        // 母音・"y" の前以外の "n" は撥音とする。
+        if s[i] == b'n'
+            && s.get(i + 1)
+                .is_none_or(|c| !VOWELS.contains(c) && *c != b'y' && *c != b'n')
+        {
+            res.push('ん');
+            i += 1;
+            continue;
+        }
+
+        // 最長一致で変換する。
+        let (len, kana) = (1..=3)
+            .rev()
+            .filter(|&len| i + len <= s.len())
+            .find_map(|len| {
+                TABLE
+                    .iter()
+                    .find(|&&(romaji, _)| romaji.as_bytes() == &s[i..i + len])
+                    .map(|&(_, kana)| (len, kana))
+            })?;
+        res.push_str(kana);
+        i += len;
+    }
+
+    Some(res)
+}
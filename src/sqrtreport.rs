@@ -0,0 +1,139 @@
+//! 厳密な平方根 ([`SqrtRoutine::Exact`]) と四捨五入した平方根 ([`SqrtRoutine::Rounded`]) で
+//! 結果が異なる箇所の報告。どちらもゲームの平方根ルーチンを再現したものではない。
+
+use crate::export::Table;
+use crate::geometry::{SqrtRoutine, Vec3};
+use crate::locale::Language;
+use crate::starmap::StarMap;
+use crate::travel::{FcRoundedSqrtRules, FcRules, MovementRules};
+
+/// 距離の計算結果が異なる座標の差。
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DeltaDisagreement {
+    /// 各方向の座標の差の絶対値を昇順に並べたもの。
+    pub delta: [u32; 3],
+    pub exact: u32,
+    pub rounded: u32,
+}
+
+/// 各方向の座標の差が `max_delta` 以下の範囲で、距離の計算結果が異なる座標の差を全て返す。
+///
+/// 距離は差の並び順に依らないので、差は昇順に並べたものだけを返す。辞書順に並ぶ。
+pub fn delta_disagreements(max_delta: u32) -> Vec<DeltaDisagreement> {
+    let origin = Vec3::new(0, 0, 0);
+    let mut res = vec![];
+    for dx in 0..=max_delta {
+        for dy in dx..=max_delta {
+            for dz in dy..=max_delta {
+                let delta = Vec3::new(dx, dy, dz);
+                let exact = delta.distance_by(origin, SqrtRoutine::Exact);
+                let rounded = delta.distance_by(origin, SqrtRoutine::Rounded);
+                if exact != rounded {
+                    res.push(DeltaDisagreement {
+                        delta: [dx, dy, dz],
+                        exact,
+                        rounded,
+                    });
+                }
+            }
+        }
+    }
+
+    res
+}
+
+/// 始点と目的地の惑星の組についての、平方根の計算方法ごとの結果。
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PlanetPairChange<T> {
+    pub src: usize,
+    pub dst: usize,
+    pub exact: T,
+    pub rounded: T,
+}
+
+/// 移動力 `speed` での (実所要ターン数, 実所要エネルギー) が異なる惑星の組を全て返す。
+pub fn travel_changes(map: &StarMap, speed: u32) -> Vec<PlanetPairChange<(u32, u32)>> {
+    pair_changes(map, |rules, src, dst| {
+        let position = |id| map.planet(id).position();
+        rules.actual_turns_and_energy(position(src), position(dst), speed)
+    })
+}
+
+/// 移動命令を出すのに必要な最小のエネルギーが異なる惑星の組を全て返す。
+pub fn order_changes(map: &StarMap) -> Vec<PlanetPairChange<Option<u32>>> {
+    pair_changes(map, |rules, src, dst| {
+        rules.min_energy_to_order(map, src, dst)
+    })
+}
+
+fn pair_changes<T, F>(map: &StarMap, f: F) -> Vec<PlanetPairChange<T>>
+where
+    T: PartialEq,
+    F: Fn(&dyn MovementRules, usize, usize) -> T,
+{
+    let n = map.planet_count();
+
+    itertools::iproduct!(0..n, 0..n)
+        .filter(|(src, dst)| src != dst)
+        .filter_map(|(src, dst)| {
+            let exact = f(&FcRules, src, dst);
+            let rounded = f(&FcRoundedSqrtRules, src, dst);
+            (exact != rounded).then_some(PlanetPairChange {
+                src,
+                dst,
+                exact,
+                rounded,
+            })
+        })
+        .collect()
+}
+
+/// [`delta_disagreements`] の結果の表を返す。
+pub fn delta_table(disagreements: &[DeltaDisagreement]) -> Table {
+    let mut table = Table::new(&["dx", "dy", "dz", "exact", "rounded"]);
+    for d in disagreements {
+        let [dx, dy, dz] = d.delta;
+        table.push_row(vec![
+            dx.into(),
+            dy.into(),
+            dz.into(),
+            d.exact.into(),
+            d.rounded.into(),
+        ]);
+    }
+
+    table
+}
+
+/// [`travel_changes`] の結果の表を返す。惑星名は `lang` の名前とする。
+pub fn travel_table(
+    map: &StarMap,
+    changes: &[PlanetPairChange<(u32, u32)>],
+    lang: Language,
+) -> Table {
+    let mut table = Table::new(&[
+        "src_id",
+        "src_name",
+        "dst_id",
+        "dst_name",
+        "exact_turns",
+        "exact_energy",
+        "rounded_turns",
+        "rounded_energy",
+    ]);
+    let name = |id| map.planet(id).name(lang);
+    for c in changes {
+        table.push_row(vec![
+            (c.src as u32).into(),
+            name(c.src).into(),
+            (c.dst as u32).into(),
+            name(c.dst).into(),
+            c.exact.0.into(),
+            c.exact.1.into(),
+            c.rounded.0.into(),
+            c.rounded.1.into(),
+        ]);
+    }
+
+    table
+}
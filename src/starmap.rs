@@ -0,0 +1,224 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Vec3;
+use crate::locale::Language;
+use crate::validate::{self, Severity, Violation};
+
+/// 座標の各成分の最大値。
+pub const COORD_MAX: u32 = 128;
+
+/// マップ画像の幅 (ピクセル)。
+pub const MAP_WIDTH: u32 = 512;
+
+/// マップ画像の高さ (ピクセル)。
+pub const MAP_HEIGHT: u32 = 480;
+
+/// マップ上の惑星画像の一辺の長さ (ピクセル)。
+pub const PLANET_IMAGE_SIZE: u32 = 32;
+
+/// 惑星の規模。マップ上の惑星画像の大きさに対応する。
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanetVolume {
+    Large,
+    Medium,
+    Small,
+    Tiny,
+}
+
+impl PlanetVolume {
+    pub fn iter() -> impl Iterator<Item = Self> + Clone {
+        use PlanetVolume::*;
+
+        [Large, Medium, Small, Tiny].into_iter()
+    }
+
+    /// マップ定義ファイルおよび画像ファイル名での表記を返す。
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Large => "large",
+            Self::Medium => "medium",
+            Self::Small => "small",
+            Self::Tiny => "tiny",
+        }
+    }
+}
+
+/// マップ定義中の 1 惑星。
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Planet {
+    name: String,
+    name_en: String,
+    position: Vec3,
+    neighbors: Vec<usize>,
+    volume: PlanetVolume,
+    /// マップ画像上での惑星画像の左上の座標 (ピクセル)。
+    map_position: (u32, u32),
+}
+
+impl Planet {
+    pub fn name(&self, lang: Language) -> &str {
+        match lang {
+            Language::Japanese => &self.name,
+            Language::English => &self.name_en,
+        }
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn neighbors(&self) -> &[usize] {
+        &self.neighbors
+    }
+
+    pub fn volume(&self) -> PlanetVolume {
+        self.volume
+    }
+
+    pub fn map_position(&self) -> (u32, u32) {
+        self.map_position
+    }
+}
+
+/// 惑星の名前・座標・隣接関係・規模・マップ上の位置をまとめたマップ定義。
+///
+/// JSON ファイルとして読み書きできる。組み込みのマップは `asset/maps/default.json`。
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct StarMap {
+    name: String,
+    /// マップの背景画像の URL。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    background: Option<String>,
+    planets: Vec<Planet>,
+}
+
+impl StarMap {
+    /// 組み込みのマップ (FC 版のマップ) を返す。
+    pub fn builtin() -> &'static Self {
+        static MAP: OnceLock<StarMap> = OnceLock::new();
+
+        MAP.get_or_init(|| {
+            Self::from_json(include_str!("../asset/maps/default.json"))
+                .expect("組み込みのマップ定義が不正")
+        })
+    }
+
+    /// JSON 形式のマップ定義を読み込み、検証する。
+    /// 警告のみなら読み込める。警告は `validate::validate` で改めて得られる。
+    pub fn from_json(s: &str) -> Result<Self, MapError> {
+        let map: Self = serde_json::from_str(s).map_err(MapError::Parse)?;
+
+        let violations = validate::validate(&map);
+        if violations.iter().any(|v| v.severity() == Severity::Error) {
+            return Err(MapError::Invalid(violations));
+        }
+
+        Ok(map)
+    }
+
+    /// JSON 形式のマップ定義を返す。
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("マップ定義は常に JSON に変換できる")
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn background(&self) -> Option<&str> {
+        self.background.as_deref()
+    }
+
+    pub fn planet_count(&self) -> usize {
+        self.planets.len()
+    }
+
+    pub fn planet(&self, id: usize) -> &Planet {
+        &self.planets[id]
+    }
+
+    pub fn planets(&self) -> &[Planet] {
+        &self.planets
+    }
+
+    /// 2 つの惑星間の距離を返す。
+    pub fn distance(&self, id1: usize, id2: usize) -> u32 {
+        self.planet(id1)
+            .position
+            .distance(self.planet(id2).position)
+    }
+
+    /// 隣接関係を (小さい ID, 大きい ID) の組として 1 回ずつ返す。
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.planets.iter().enumerate().flat_map(|(id, planet)| {
+            planet
+                .neighbors
+                .iter()
+                .filter(move |&&dst| id < dst)
+                .map(move |&dst| (id, dst))
+        })
+    }
+
+    pub fn set_position(&mut self, id: usize, position: Vec3) {
+        self.planets[id].position = position;
+    }
+
+    pub fn set_map_position(&mut self, id: usize, map_position: (u32, u32)) {
+        self.planets[id].map_position = map_position;
+    }
+
+    /// 2 つの惑星を隣接させる。隣接関係は双方向に登録する。
+    /// 同一の惑星か、既に隣接していれば何もせず `false` を返す。
+    pub fn connect(&mut self, id1: usize, id2: usize) -> bool {
+        if id1 == id2 || self.planets[id1].neighbors.contains(&id2) {
+            return false;
+        }
+        self.planets[id1].neighbors.push(id2);
+        self.planets[id1].neighbors.sort_unstable();
+        self.planets[id2].neighbors.push(id1);
+        self.planets[id2].neighbors.sort_unstable();
+
+        true
+    }
+
+    /// 2 つの惑星の隣接関係を双方向とも削除する。隣接していなければ `false` を返す。
+    pub fn disconnect(&mut self, id1: usize, id2: usize) -> bool {
+        let len = self.planets[id1].neighbors.len();
+        self.planets[id1].neighbors.retain(|&id| id != id2);
+        self.planets[id2].neighbors.retain(|&id| id != id1);
+
+        self.planets[id1].neighbors.len() != len
+    }
+}
+
+#[derive(Debug)]
+pub enum MapError {
+    Parse(serde_json::Error),
+    /// 重大度が `Severity::Error` の違反がある。警告も含めて全ての違反を持つ。
+    Invalid(Vec<Violation>),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "JSON として読み込めない: {e}"),
+            Self::Invalid(violations) => {
+                let lines: Vec<_> = violations.iter().map(|v| v.to_string()).collect();
+                write!(f, "マップ定義が不正:\n{}", lines.join("\n"))
+            }
+        }
+    }
+}
+
+impl std::error::Error for MapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            Self::Invalid(_) => None,
+        }
+    }
+}
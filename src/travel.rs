@@ -1,69 +1,281 @@
-use crate::geometry::Vec3;
-use crate::planet::{planet_distance, planet_neighbors, PLANET_COUNT};
-
-/// 現在地の惑星とエネルギーを与えたときに移動命令を出せる惑星の集合を返す。
-/// 結果は `bool` 配列として返す。
-pub fn reachable_planets(src: usize, energy: u32) -> [bool; PLANET_COUNT] {
-    #[derive(Debug)]
-    struct Dfs {
+use std::fmt;
+
+use crate::geometry::{SqrtRoutine, Vec3};
+use crate::planetset::PlanetSet;
+use crate::starmap::StarMap;
+
+/// 移動のルール。移動命令を出せるかどうかの判定、1 ターンの移動、エネルギーの消費を定める。
+///
+/// 経路探索や所要ターン数の計算はこれらを組み合わせて行う。既定の実装は [`FcRules`]。
+pub trait MovementRules: fmt::Debug {
+    /// 現在地からエネルギー `energy` で `dst` への移動命令を出せるかどうかを返す。
+    ///
+    /// エネルギーについて単調 (出せるならより多いエネルギーでも出せる) でなければならない。
+    fn can_order(&self, energy: u32, src: Vec3, dst: Vec3) -> bool;
+
+    /// 艦隊の現在地、目的地、移動力を与えたときの現ターンの移動先を返す。
+    /// `src != target` なら `src` より `target` に近づかなければならない。
+    fn step(&self, src: Vec3, target: Vec3, speed: u32) -> Vec3;
+
+    /// 1 ターンの移動で消費するエネルギーを返す。
+    fn step_energy(&self, src: Vec3, dst: Vec3) -> u32;
+
+    /// このルールでの 2 点間の距離を返す。表示などに使う。
+    fn distance(&self, src: Vec3, dst: Vec3) -> u32 {
+        src.distance(dst)
+    }
+
+    /// 現在地の惑星とエネルギーを与えたときに移動命令を出せる惑星の集合を返す。
+    fn reachable_planets(&self, map: &StarMap, src: usize, energy: u32) -> PlanetSet {
+        self.reachable_planets_and_edges(map, src, energy).0
+    }
+
+    /// 現在地の惑星とエネルギーを与えたときに、移動命令を出せる惑星を探索する過程で辿った隣接関係を返す。
+    /// 各要素は (探索元, 探索先) で、探索順に並ぶ。
+    fn reachable_edges(&self, map: &StarMap, src: usize, energy: u32) -> Vec<(usize, usize)> {
+        self.reachable_planets_and_edges(map, src, energy).1
+    }
+
+    /// [`MovementRules::reachable_planets`] と [`MovementRules::reachable_edges`] の結果を 1 回の探索で返す。
+    fn reachable_planets_and_edges(
+        &self,
+        map: &StarMap,
         src: usize,
         energy: u32,
-        reachables: [bool; PLANET_COUNT],
-    }
-    impl Dfs {
-        fn new(src: usize, energy: u32) -> Self {
-            Self {
-                src,
-                energy,
-                reachables: [false; PLANET_COUNT],
+    ) -> (PlanetSet, Vec<(usize, usize)>) {
+        let mut dfs = Dfs::new(self, map, src, energy);
+        dfs.solve();
+
+        (dfs.reachables, dfs.edges)
+    }
+
+    /// 現在地の惑星から目的地の惑星へ移動命令を出すのに必要な最小のエネルギーを返す。
+    /// エネルギーがいくらあっても移動命令を出せない場合、`None` を返す。
+    ///
+    /// `src == dst` の場合、`Some(0)` を返す。
+    fn min_energy_to_order(&self, map: &StarMap, src: usize, dst: usize) -> Option<u32> {
+        let reachable = |energy| self.reachable_planets(map, src, energy).contains(dst);
+        if !reachable(u32::MAX) {
+            return None;
+        }
+
+        // 移動命令を出せる惑星の集合はエネルギーについて単調なので二分探索する。
+        let (mut lo, mut hi) = (0, u32::MAX);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if reachable(mid) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
             }
         }
-        fn solve(&mut self) {
-            self.reachables[self.src] = true;
-            self.dfs(self.src);
+
+        Some(lo)
+    }
+
+    /// 艦隊の現在地、目的地、移動力を与えたときの到着までの (実所要ターン数, 実所要エネルギー) を返す。
+    ///
+    /// `src == target` の場合、`(0, 0)` を返す。
+    ///
+    /// # Panics
+    ///
+    /// `step` が移動しなかった場合 (FC 版のルールでは移動力 1 で起こりうる)。到着しないまま計算を続けず、パニックする。
+    fn actual_turns_and_energy(&self, src: Vec3, target: Vec3, speed: u32) -> (u32, u32) {
+        // 愚直にシミュレートする。
+
+        let mut turn = 0;
+        let mut energy = 0;
+        let mut p = src;
+
+        while p != target {
+            let p_nxt = self.step(p, target, speed);
+            assert_moved(p, p_nxt, speed);
+            turn += 1;
+            energy += self.step_energy(p, p_nxt);
+            p = p_nxt;
         }
-        fn dfs(&mut self, id: usize) {
-            for &dst in planet_neighbors(id) {
-                if self.reachables[dst] {
-                    continue;
-                }
-                if self.energy > planet_distance(self.src, dst) {
-                    self.reachables[dst] = true;
-                    self.dfs(dst);
-                }
-            }
+
+        (turn, energy)
+    }
+
+    /// 艦隊の現在地、目的地、移動力を与えたときの各ターン終了時の位置を返す。
+    ///
+    /// 先頭要素は現在地、末尾要素は目的地となる。`src == target` の場合、`[src]` を返す。
+    ///
+    /// # Panics
+    ///
+    /// [`MovementRules::actual_turns_and_energy`] と同じく、`step` が移動しなかった場合。
+    fn fleet_path(&self, src: Vec3, target: Vec3, speed: u32) -> Vec<Vec3> {
+        let mut path = vec![src];
+        let mut p = src;
+
+        while p != target {
+            let p_nxt = self.step(p, target, speed);
+            assert_moved(p, p_nxt, speed);
+            p = p_nxt;
+            path.push(p);
         }
+
+        path
     }
+}
 
-    let mut dfs = Dfs::new(src, energy);
-    dfs.solve();
+/// 到着までの計算で、1 ターンの移動で位置が変わらなければパニックする。そのままでは計算が終わらない。
+fn assert_moved(p: Vec3, p_nxt: Vec3, speed: u32) {
+    assert!(
+        p != p_nxt,
+        "移動力 {speed} では ({}, {}, {}) から移動しない",
+        p.x,
+        p.y,
+        p.z
+    );
+}
 
-    dfs.reachables
+/// FC 版のルール。
+///
+/// 現在地から目的地までの距離より多いエネルギーがあれば移動命令を出せる。
+/// 各ターンは x, y, z 各方向について残り移動距離を所要ターン数で割った値 (切り捨て) だけ移動し、
+/// 移動した距離だけエネルギーを消費する。
+///
+/// ゲームの移動力は 10 以上で、移動力は 2 以上で使う。移動力が 1 だと移動量が全て切り捨てられて
+/// 移動しない場合があり、到着までの計算はパニックする。移動力 0 では `step` がパニックする。
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FcRules;
+
+impl MovementRules for FcRules {
+    fn can_order(&self, energy: u32, src: Vec3, dst: Vec3) -> bool {
+        energy > self.distance(src, dst)
+    }
+
+    fn step(&self, src: Vec3, target: Vec3, speed: u32) -> Vec3 {
+        move_fleet(src, target, speed, SqrtRoutine::Exact)
+    }
+
+    fn step_energy(&self, src: Vec3, dst: Vec3) -> u32 {
+        self.distance(src, dst)
+    }
 }
 
-/// 艦隊の現在地、目的地、移動力を与えたときの到着までの (実所要ターン数, 実所要エネルギー) を返す。
+/// FC 版のルールで、距離の計算に四捨五入した平方根 ([`SqrtRoutine::Rounded`]) を使うもの。
 ///
-/// `src == target` の場合、`(0, 0)` を返す。
+/// 平方根の丸め方による結果の違いを調べるためのもので、ゲームの挙動を再現したものではない。
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FcRoundedSqrtRules;
+
+impl MovementRules for FcRoundedSqrtRules {
+    fn can_order(&self, energy: u32, src: Vec3, dst: Vec3) -> bool {
+        energy > self.distance(src, dst)
+    }
+
+    fn step(&self, src: Vec3, target: Vec3, speed: u32) -> Vec3 {
+        move_fleet(src, target, speed, SqrtRoutine::Rounded)
+    }
+
+    fn step_energy(&self, src: Vec3, dst: Vec3) -> u32 {
+        self.distance(src, dst)
+    }
+
+    fn distance(&self, src: Vec3, dst: Vec3) -> u32 {
+        src.distance_by(dst, SqrtRoutine::Rounded)
+    }
+}
+
+/// 平方根の計算方法に対応する FC 版のルールを返す。
+pub fn fc_rules(sqrt: SqrtRoutine) -> &'static dyn MovementRules {
+    match sqrt {
+        SqrtRoutine::Exact => &FcRules,
+        SqrtRoutine::Rounded => &FcRoundedSqrtRules,
+    }
+}
+
+/// FC 版のルールで移動命令を出せる惑星の集合を返す。[`MovementRules::reachable_planets`] を参照。
+pub fn reachable_planets(map: &StarMap, src: usize, energy: u32) -> PlanetSet {
+    FcRules.reachable_planets(map, src, energy)
+}
+
+/// FC 版のルールで探索した隣接関係を返す。[`MovementRules::reachable_edges`] を参照。
+pub fn reachable_edges(map: &StarMap, src: usize, energy: u32) -> Vec<(usize, usize)> {
+    FcRules.reachable_edges(map, src, energy)
+}
+
+/// FC 版のルールで移動命令を出せる惑星の集合と探索した隣接関係を返す。
+/// [`MovementRules::reachable_planets_and_edges`] を参照。
+pub fn reachable_planets_and_edges(
+    map: &StarMap,
+    src: usize,
+    energy: u32,
+) -> (PlanetSet, Vec<(usize, usize)>) {
+    FcRules.reachable_planets_and_edges(map, src, energy)
+}
+
+/// FC 版のルールで移動命令を出すのに必要な最小のエネルギーを返す。
+/// [`MovementRules::min_energy_to_order`] を参照。
+pub fn min_energy_to_order(map: &StarMap, src: usize, dst: usize) -> Option<u32> {
+    FcRules.min_energy_to_order(map, src, dst)
+}
+
+/// FC 版のルールでの到着までの (実所要ターン数, 実所要エネルギー) を返す。
+/// [`MovementRules::actual_turns_and_energy`] を参照。
 pub fn actual_turns_and_energy(src: Vec3, target: Vec3, speed: u32) -> (u32, u32) {
-    // 愚直にシミュレートする。
+    FcRules.actual_turns_and_energy(src, target, speed)
+}
+
+/// FC 版のルールでの各ターン終了時の位置を返す。[`MovementRules::fleet_path`] を参照。
+pub fn fleet_path(src: Vec3, target: Vec3, speed: u32) -> Vec<Vec3> {
+    FcRules.fleet_path(src, target, speed)
+}
 
-    let mut turn = 0;
-    let mut energy = 0;
-    let mut p = src;
+#[derive(Debug)]
+struct Dfs<'a, R: ?Sized> {
+    rules: &'a R,
+    map: &'a StarMap,
+    src: usize,
+    energy: u32,
+    reachables: PlanetSet,
+    edges: Vec<(usize, usize)>,
+}
 
-    while p != target {
-        let p_nxt = move_fleet(p, target, speed);
-        turn += 1;
-        energy += p.distance(p_nxt);
-        p = p_nxt;
+impl<'a, R: MovementRules + ?Sized> Dfs<'a, R> {
+    fn new(rules: &'a R, map: &'a StarMap, src: usize, energy: u32) -> Self {
+        Self {
+            rules,
+            map,
+            src,
+            energy,
+            reachables: PlanetSet::new(map.planet_count()),
+            edges: vec![],
+        }
+    }
+
+    fn solve(&mut self) {
+        self.reachables.insert(self.src);
+        self.dfs(self.src);
     }
 
-    (turn, energy)
+    fn dfs(&mut self, id: usize) {
+        let map = self.map;
+        let src_pos = map.planet(self.src).position();
+        for &dst in map.planet(id).neighbors() {
+            if self.reachables.contains(dst) {
+                continue;
+            }
+            if self
+                .rules
+                .can_order(self.energy, src_pos, map.planet(dst).position())
+            {
+                self.reachables.insert(dst);
+                self.edges.push((id, dst));
+                self.dfs(dst);
+            }
+        }
+    }
 }
 
-/// 艦隊の現在地、目的地、移動力を与えたときの現ターンの移動先を返す。
-fn move_fleet(src: Vec3, target: Vec3, speed: u32) -> Vec3 {
-    let dist_to_target = src.distance(target);
+/// FC 版の、艦隊の現在地、目的地、移動力を与えたときの現ターンの移動先を返す。
+fn move_fleet(src: Vec3, target: Vec3, speed: u32, sqrt: SqrtRoutine) -> Vec3 {
+    assert!(speed > 0, "移動力が 0");
+
+    let dist_to_target = src.distance_by(target, sqrt);
 
     // 現ターンで目的地に到達できるなら、目的地の座標を返す。
     if speed >= dist_to_target {
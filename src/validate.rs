@@ -0,0 +1,310 @@
+use std::fmt;
+
+use crate::locale::Language;
+use crate::starmap::{StarMap, COORD_MAX, MAP_HEIGHT, MAP_WIDTH, PLANET_IMAGE_SIZE};
+
+/// 違反の重大度。
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// 経路探索などが正しく動作しない。このようなマップは読み込めない。
+    Error,
+    /// 動作はするが、マップの誤りである可能性が高い。
+    Warning,
+}
+
+/// マップ定義の違反の種類。惑星は ID で表す。
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ViolationKind {
+    NoPlanets,
+    NeighborOutOfRange {
+        id: usize,
+        neighbor: usize,
+    },
+    SelfLoop {
+        id: usize,
+    },
+    DuplicateNeighbor {
+        id: usize,
+        neighbor: usize,
+    },
+    /// `id` の隣接惑星 `neighbor` の隣接惑星に `id` が含まれない。
+    Asymmetric {
+        id: usize,
+        neighbor: usize,
+    },
+    /// 惑星 0 から隣接関係を辿って到達できない連結成分。
+    Disconnected {
+        planets: Vec<usize>,
+    },
+    /// 座標のいずれかの成分が `COORD_MAX` を超える。
+    PositionOutOfBounds {
+        id: usize,
+    },
+    SamePosition {
+        id1: usize,
+        id2: usize,
+    },
+    /// 惑星画像がマップ画像からはみ出す。
+    MapPositionOutOfBounds {
+        id: usize,
+    },
+    SameMapPosition {
+        id1: usize,
+        id2: usize,
+    },
+    EmptyName {
+        id: usize,
+    },
+}
+
+impl ViolationKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::NoPlanets
+            | Self::NeighborOutOfRange { .. }
+            | Self::SelfLoop { .. }
+            | Self::DuplicateNeighbor { .. }
+            | Self::Asymmetric { .. }
+            | Self::PositionOutOfBounds { .. } => Severity::Error,
+            Self::Disconnected { .. }
+            | Self::SamePosition { .. }
+            | Self::MapPositionOutOfBounds { .. }
+            | Self::SameMapPosition { .. }
+            | Self::EmptyName { .. } => Severity::Warning,
+        }
+    }
+}
+
+/// マップ定義の違反 1 件。説明は惑星の ID と名前を含み、言語ごとに持つ。
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    message_ja: String,
+    message_en: String,
+}
+
+impl Violation {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+
+    pub fn message(&self, lang: Language) -> &str {
+        match lang {
+            Language::Japanese => &self.message_ja,
+            Language::English => &self.message_en,
+        }
+    }
+}
+
+/// 日本語で重大度と説明を表示する。
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity() {
+            Severity::Error => "エラー",
+            Severity::Warning => "警告",
+        };
+        write!(f, "{severity}: {}", self.message(Language::Japanese))
+    }
+}
+
+/// マップ定義の整合性を検査し、全ての違反を返す。
+///
+/// 隣接関係の対称性・自己ループ・重複・連結性、座標やマップ上の位置の重複などを検査する。
+pub fn validate(map: &StarMap) -> Vec<Violation> {
+    let n = map.planet_count();
+
+    let mut kinds = vec![];
+
+    if n == 0 {
+        kinds.push(ViolationKind::NoPlanets);
+    }
+
+    for (id, planet) in map.planets().iter().enumerate() {
+        if planet.name(Language::Japanese).is_empty() || planet.name(Language::English).is_empty() {
+            kinds.push(ViolationKind::EmptyName { id });
+        }
+
+        for (i, &neighbor) in planet.neighbors().iter().enumerate() {
+            if neighbor >= n {
+                kinds.push(ViolationKind::NeighborOutOfRange { id, neighbor });
+            } else if neighbor == id {
+                kinds.push(ViolationKind::SelfLoop { id });
+            } else if planet.neighbors()[..i].contains(&neighbor) {
+                kinds.push(ViolationKind::DuplicateNeighbor { id, neighbor });
+            } else if !map.planet(neighbor).neighbors().contains(&id) {
+                kinds.push(ViolationKind::Asymmetric { id, neighbor });
+            }
+        }
+
+        let position = planet.position();
+        if [position.x, position.y, position.z]
+            .iter()
+            .any(|&c| c > COORD_MAX)
+        {
+            kinds.push(ViolationKind::PositionOutOfBounds { id });
+        }
+
+        let (x, y) = planet.map_position();
+        let out_of_bounds =
+            |c: u32, size| c.checked_add(PLANET_IMAGE_SIZE).is_none_or(|r| r > size);
+        if out_of_bounds(x, MAP_WIDTH) || out_of_bounds(y, MAP_HEIGHT) {
+            kinds.push(ViolationKind::MapPositionOutOfBounds { id });
+        }
+    }
+
+    for (id1, id2) in (0..n).flat_map(|id1| (id1 + 1..n).map(move |id2| (id1, id2))) {
+        if map.planet(id1).position() == map.planet(id2).position() {
+            kinds.push(ViolationKind::SamePosition { id1, id2 });
+        }
+        if map.planet(id1).map_position() == map.planet(id2).map_position() {
+            kinds.push(ViolationKind::SameMapPosition { id1, id2 });
+        }
+    }
+
+    kinds.extend(
+        disconnected_components(map)
+            .into_iter()
+            .map(|planets| ViolationKind::Disconnected { planets }),
+    );
+
+    kinds
+        .into_iter()
+        .map(|kind| Violation {
+            message_ja: message(map, &kind, Language::Japanese),
+            message_en: message(map, &kind, Language::English),
+            kind,
+        })
+        .collect()
+}
+
+/// 違反の説明。惑星は ID と `lang` の名前で表す。
+fn message(map: &StarMap, kind: &ViolationKind, lang: Language) -> String {
+    let name = |id: usize| {
+        if id < map.planet_count() {
+            format!("{id}:{}", map.planet(id).name(lang))
+        } else {
+            id.to_string()
+        }
+    };
+    let names = |planets: &[usize]| {
+        let names: Vec<_> = planets.iter().map(|&id| name(id)).collect();
+        names.join(", ")
+    };
+
+    match lang {
+        Language::Japanese => match kind {
+            ViolationKind::NoPlanets => "惑星がない".to_owned(),
+            ViolationKind::NeighborOutOfRange { id, neighbor } => {
+                format!("惑星 {} の隣接惑星 {neighbor} が存在しない", name(*id))
+            }
+            ViolationKind::SelfLoop { id } => {
+                format!("惑星 {} が自身に隣接している", name(*id))
+            }
+            ViolationKind::DuplicateNeighbor { id, neighbor } => format!(
+                "惑星 {} の隣接惑星に {} が重複している",
+                name(*id),
+                name(*neighbor)
+            ),
+            ViolationKind::Asymmetric { id, neighbor } => format!(
+                "惑星 {} は {} に隣接するが、逆が成り立たない",
+                name(*id),
+                name(*neighbor)
+            ),
+            ViolationKind::Disconnected { planets } => format!(
+                "惑星 {} から到達できない惑星がある: {}",
+                name(0),
+                names(planets)
+            ),
+            ViolationKind::PositionOutOfBounds { id } => {
+                format!("惑星 {} の座標が 0〜{COORD_MAX} の範囲外", name(*id))
+            }
+            ViolationKind::SamePosition { id1, id2 } => {
+                format!("惑星 {} と {} の座標が同じ", name(*id1), name(*id2))
+            }
+            ViolationKind::MapPositionOutOfBounds { id } => {
+                format!("惑星 {} のマップ上の位置が範囲外", name(*id))
+            }
+            ViolationKind::SameMapPosition { id1, id2 } => format!(
+                "惑星 {} と {} のマップ上の位置が同じ",
+                name(*id1),
+                name(*id2)
+            ),
+            ViolationKind::EmptyName { id } => format!("惑星 {id} の名前が空"),
+        },
+        Language::English => match kind {
+            ViolationKind::NoPlanets => "There are no planets".to_owned(),
+            ViolationKind::NeighborOutOfRange { id, neighbor } => {
+                format!("Neighbor {neighbor} of planet {} does not exist", name(*id))
+            }
+            ViolationKind::SelfLoop { id } => {
+                format!("Planet {} is its own neighbor", name(*id))
+            }
+            ViolationKind::DuplicateNeighbor { id, neighbor } => format!(
+                "Planet {} lists {} as a neighbor more than once",
+                name(*id),
+                name(*neighbor)
+            ),
+            ViolationKind::Asymmetric { id, neighbor } => format!(
+                "Planet {} is adjacent to {}, but not the other way round",
+                name(*id),
+                name(*neighbor)
+            ),
+            ViolationKind::Disconnected { planets } => format!(
+                "Some planets are unreachable from planet {}: {}",
+                name(0),
+                names(planets)
+            ),
+            ViolationKind::PositionOutOfBounds { id } => format!(
+                "Coordinates of planet {} are outside 0 to {COORD_MAX}",
+                name(*id)
+            ),
+            ViolationKind::SamePosition { id1, id2 } => format!(
+                "Planets {} and {} have the same coordinates",
+                name(*id1),
+                name(*id2)
+            ),
+            ViolationKind::MapPositionOutOfBounds { id } => {
+                format!("Map position of planet {} is out of bounds", name(*id))
+            }
+            ViolationKind::SameMapPosition { id1, id2 } => format!(
+                "Planets {} and {} have the same map position",
+                name(*id1),
+                name(*id2)
+            ),
+            ViolationKind::EmptyName { id } => format!("Planet {id} has an empty name"),
+        },
+    }
+}
+
+/// 惑星 0 を含まない連結成分を、それぞれ ID の昇順で返す。範囲外の隣接惑星は無視する。
+fn disconnected_components(map: &StarMap) -> Vec<Vec<usize>> {
+    let n = map.planet_count();
+
+    let mut component = vec![None; n];
+    let mut count = 0;
+    for start in 0..n {
+        if component[start].is_some() {
+            continue;
+        }
+        component[start] = Some(count);
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            // 隣接関係が非対称でも連結性は無向グラフとして判定する。
+            let neighbors =
+                map.planet(id).neighbors().iter().copied().chain(
+                    (0..n).filter(move |&other| map.planet(other).neighbors().contains(&id)),
+                );
+            for dst in neighbors.filter(|&dst| dst < n) {
+                if component[dst].is_none() {
+                    component[dst] = Some(count);
+                    stack.push(dst);
+                }
+            }
+        }
+        count += 1;
+    }
+
+    (1..count)
+        .map(|c| (0..n).filter(|&id| component[id] == Some(c)).collect())
+        .collect()
+}
@@ -1,7 +1,32 @@
 use seed::{prelude::*, *};
 
-use crate::planet::{planet_name, planet_position, PLANET_COUNT};
+use crate::geometry::Vec3;
+use crate::locale::Language;
 use crate::query::{Answer, Query, QueryPlanet, QueryPosition};
+use crate::search::search_planets;
+use crate::starmap::{PlanetVolume, StarMap, COORD_MAX, MAP_HEIGHT, MAP_WIDTH};
+use crate::travel::{FcRules, MovementRules};
+
+mod detail;
+mod editor;
+mod graph;
+mod mapfile;
+mod matrix;
+mod playback;
+mod projection;
+mod sqrtreport;
+mod table;
+mod text;
+mod viewport;
+
+use self::editor::{Editor, EditorMsg};
+use self::mapfile::{MapFileMsg, MapFileState};
+use self::playback::{Playback, PlaybackMsg};
+use self::projection::Projection;
+use self::sqrtreport::SqrtReport;
+use self::table::{TableMsg, TableState};
+use self::text::Text;
+use self::viewport::{Viewport, ViewportMsg};
 
 #[wasm_bindgen(start)]
 pub fn start() {
@@ -12,44 +37,308 @@ const SPEEDS: [u32; 5] = [30, 20, 16, 12, 10];
 
 const ENERGY_MAX: u32 = 100;
 
+/// 設定を保存する localStorage のキー。
+const STORAGE_KEY: &str = "logh-travel-settings";
+
 #[derive(Debug)]
 struct Model {
+    lang: Language,
+    map: StarMap,
+    map_file: MapFileState,
+    editor: Editor,
+    page: Page,
     speed: u32,
+    compare_speeds: bool,
     query: Query,
+    /// 始点の惑星を選ぶための検索語。
+    planet_search: String,
+    matrix_energy: u32,
+    playback: Playback,
+    projection: Projection,
+    sqrt_report: Option<SqrtReport>,
+    detail: Option<usize>,
+    table: TableState,
+    viewport: Viewport,
 }
 
 impl Default for Model {
     fn default() -> Self {
         Self {
+            lang: Default::default(),
+            map: StarMap::builtin().clone(),
+            map_file: Default::default(),
+            editor: Default::default(),
+            page: Page::Query,
             speed: SPEEDS[0],
+            compare_speeds: false,
             query: Default::default(),
+            planet_search: String::new(),
+            matrix_energy: ENERGY_MAX,
+            playback: Default::default(),
+            projection: Default::default(),
+            sqrt_report: None,
+            detail: None,
+            table: Default::default(),
+            viewport: Default::default(),
+        }
+    }
+}
+
+impl Model {
+    fn text(&self) -> &'static Text {
+        text::text(self.lang)
+    }
+
+    /// 移動の計算に使うルールを返す。
+    fn rules(&self) -> &'static dyn MovementRules {
+        &FcRules
+    }
+
+    /// 表示言語での惑星名を返す。
+    fn planet_name(&self, id: usize) -> &str {
+        self.map.planet(id).name(self.lang)
+    }
+
+    /// URL のクエリ文字列が表す移動力と始点を反映する。
+    /// 該当するパラメータがないか不正な場合は既定値とする。
+    ///
+    /// 形式は `?speed=30&src=0&energy=100` (惑星指定) または `?speed=30&pos=8,8,8` (座標指定)。
+    fn load_url(&mut self, url: &Url) {
+        self.load_search(url.search());
+    }
+
+    fn load_search(&mut self, search: &UrlSearch) {
+        let param = |key: &str| search.get(key).and_then(|values| values.first());
+
+        self.speed = param("speed")
+            .and_then(|s| s.parse::<u32>().ok())
+            .filter(|speed| SPEEDS.contains(speed))
+            .unwrap_or(SPEEDS[0]);
+
+        let query_planet = param("src")
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|&src| src < self.map.planet_count())
+            .map(|src| {
+                let energy = param("energy")
+                    .and_then(|s| s.parse::<u32>().ok())
+                    .map_or(ENERGY_MAX, |energy| energy.min(ENERGY_MAX));
+                Query::new_planet(src, energy)
+            });
+        // 入力欄と同じく、範囲外の座標は `COORD_MAX` に丸める。
+        let query_position = param("pos").and_then(|s| {
+            let xyz: Vec<_> = s
+                .split(',')
+                .map(|x| x.parse::<u32>().ok().map(|c| c.min(COORD_MAX)))
+                .collect();
+            match xyz[..] {
+                [Some(x), Some(y), Some(z)] => Some(Query::new_position(Vec3::new(x, y, z))),
+                _ => None,
+            }
+        });
+        self.query = query_planet.or(query_position).unwrap_or_default();
+    }
+
+    /// 移動力と始点を表す URL のクエリ文字列を返す。
+    fn url_search(&self) -> UrlSearch {
+        let mut search = UrlSearch::default();
+
+        search.push_value("speed", self.speed.to_string());
+        match &self.query {
+            Query::Planet(q) => {
+                search.push_value("src", q.src().to_string());
+                search.push_value("energy", q.energy().to_string());
+            }
+            Query::Position(q) => {
+                let Vec3 { x, y, z } = q.src();
+                search.push_value("pos", format!("{x},{y},{z}"));
+            }
+        }
+
+        search
+    }
+
+    /// localStorage に保存された設定を反映する。
+    /// URL に含まれる状態に加え、表示ページなどの URL に含めない状態も復元する。
+    fn load_storage(&mut self) {
+        let Ok(saved) = LocalStorage::get::<_, String>(STORAGE_KEY) else {
+            return;
+        };
+        let Ok(params) = web_sys::UrlSearchParams::new_with_str(&saved) else {
+            return;
+        };
+        let search = UrlSearch::from(params);
+        let param = |key: &str| search.get(key).and_then(|values| values.first());
+
+        self.load_search(&search);
+        if let Some(lang) = param("lang").and_then(|s| Language::from_code(s)) {
+            self.lang = lang;
+        }
+        if let Some(page) = param("page").and_then(|s| Page::iter().find(|page| page.key() == s)) {
+            self.page = page;
+        }
+        self.compare_speeds = param("compare").is_some_and(|s| s == "1");
+        if let Some(energy) = param("matrix_energy").and_then(|s| s.parse::<u32>().ok()) {
+            self.matrix_energy = energy;
+        }
+        if let Some(projection) =
+            param("projection").and_then(|s| Projection::iter().find(|p| p.key() == s))
+        {
+            self.projection = projection;
+        }
+    }
+
+    /// localStorage に保存する設定を返す。
+    ///
+    /// 保存するのは表示や計算の設定のみで、利用者定義の艦隊やプリセットは扱わない。
+    fn storage_search(&self) -> UrlSearch {
+        let mut search = self.url_search();
+        search.push_value("lang", self.lang.code().to_owned());
+        search.push_value("page", self.page.key().to_owned());
+        search.push_value("compare", u8::from(self.compare_speeds).to_string());
+        search.push_value("matrix_energy", self.matrix_energy.to_string());
+        search.push_value("projection", self.projection.key().to_owned());
+
+        search
+    }
+
+    fn save_storage(&self) {
+        // 保存に失敗しても動作には影響しないので無視する。
+        let _ = LocalStorage::insert(STORAGE_KEY, &self.storage_search().to_string());
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Page {
+    Query,
+    Matrix,
+    Projection,
+    Map,
+    Graph,
+    Sqrt,
+}
+
+impl Page {
+    fn iter() -> impl Iterator<Item = Self> + Clone {
+        use Page::*;
+
+        [Query, Matrix, Projection, Map, Graph, Sqrt].into_iter()
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Query => "query",
+            Self::Matrix => "matrix",
+            Self::Projection => "projection",
+            Self::Map => "map",
+            Self::Graph => "graph",
+            Self::Sqrt => "sqrt",
+        }
+    }
+
+    fn label(self, t: &Text) -> &'static str {
+        match self {
+            Self::Query => t.page_query,
+            Self::Matrix => t.page_matrix,
+            Self::Projection => t.page_projection,
+            Self::Map => t.page_map,
+            Self::Graph => t.page_graph,
+            Self::Sqrt => t.page_sqrt,
         }
     }
 }
 
 #[derive(Debug)]
 enum Msg {
+    UrlChanged(subs::UrlChanged),
+    ResetSettings,
+    SetLanguage(Language),
+
+    SetPage(Page),
     SetSpeed(u32),
+    ToggleCompareSpeeds,
     SetSourcePlanet(usize),
     SetSourcePosition,
+    SetPlanetSearch(String),
 
     SetQueryPlanetEnergy(u32),
     SetQueryPositionX(u32),
     SetQueryPositionY(u32),
     SetQueryPositionZ(u32),
+
+    SetMatrixEnergy(u32),
+    SetProjection(Projection),
+    ShowPlanetDetail(usize),
+
+    MapFile(MapFileMsg),
+    Editor(EditorMsg),
+    Playback(PlaybackMsg),
+    Table(TableMsg),
+    Viewport(ViewportMsg),
 }
 
-fn init(_url: Url, _orders: &mut impl Orders<Msg>) -> Model {
-    Model::default()
+fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
+    orders.subscribe(Msg::UrlChanged);
+
+    // URL で状態が指定されていればそちらを localStorage の設定より優先する。
+    let mut model = Model::default();
+    mapfile::load_storage(&mut model);
+    model.load_storage();
+    if url.search().iter().next().is_some() {
+        model.load_url(&url);
+    }
+    url.set_search(model.url_search()).go_and_replace();
+    sqrtreport::refresh(&mut model);
+
+    model
 }
 
-fn update(msg: Msg, model: &mut Model, _orders: &mut impl Orders<Msg>) {
+fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    // ブラウザの戻る/進むなどで URL が変わったら状態を復元する。
+    // それ以外で URL に含まれる状態が変わったら履歴に追加する。
+    if let Msg::UrlChanged(subs::UrlChanged(url)) = msg {
+        model.load_url(&url);
+        sqrtreport::refresh(model);
+        return;
+    }
+
+    // 再生の進行などでは設定は変わらないので、設定が変わったときだけ保存する。
+    // 初期化した直後は既定値を保存し直さない。
+    let reset = matches!(msg, Msg::ResetSettings);
+    let settings = model.storage_search();
+    let search = model.url_search();
+    update_model(msg, model, orders);
+    sqrtreport::refresh(model);
+    let search_new = model.url_search();
+    if search_new != search {
+        Url::current().set_search(search_new).go_and_push();
+    }
+
+    if !reset && model.storage_search() != settings {
+        model.save_storage();
+    }
+}
+
+fn update_model(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     match msg {
+        Msg::UrlChanged(_) => {}
+        Msg::ResetSettings => {
+            let _ = LocalStorage::remove(STORAGE_KEY);
+            let _ = LocalStorage::remove(mapfile::STORAGE_KEY);
+            *model = Model::default();
+        }
+        Msg::SetLanguage(lang) => model.lang = lang,
+        Msg::SetPage(page) => model.page = page,
         Msg::SetSpeed(speed) => model.speed = speed,
-        Msg::SetSourcePlanet(src) => match &mut model.query {
-            Query::Planet(q) => q.set_src(src),
-            Query::Position(_) => model.query = Query::new_planet(src, ENERGY_MAX),
-        },
+        Msg::ToggleCompareSpeeds => model.compare_speeds = !model.compare_speeds,
+        Msg::SetSourcePlanet(src) => {
+            match &mut model.query {
+                Query::Planet(q) => q.set_src(src),
+                Query::Position(_) => model.query = Query::new_planet(src, ENERGY_MAX),
+            }
+            model.detail = Some(src);
+            model.planet_search.clear();
+        }
+        Msg::SetPlanetSearch(s) => model.planet_search = s,
         Msg::SetSourcePosition => {
             if matches!(model.query, Query::Planet(_)) {
                 model.query = Query::default_position();
@@ -75,28 +364,135 @@ fn update(msg: Msg, model: &mut Model, _orders: &mut impl Orders<Msg>) {
                 q.set_src_z(z);
             }
         }
+        Msg::SetMatrixEnergy(energy) => model.matrix_energy = energy,
+        Msg::SetProjection(projection) => model.projection = projection,
+        Msg::ShowPlanetDetail(id) => model.detail = Some(id),
+        Msg::MapFile(msg) => mapfile::update(msg, model),
+        Msg::Editor(msg) => editor::update(msg, model),
+        Msg::Playback(msg) => playback::update(msg, model, orders),
+        Msg::Table(msg) => table::update(msg, &mut model.table),
+        Msg::Viewport(msg) => {
+            if !editor::handle_pointer(&msg, model) {
+                viewport::update(msg, &mut model.viewport);
+            }
+        }
     }
 }
 
 fn view(model: &Model) -> Node<Msg> {
-    let ans = model.query.execute();
+    let page = match model.page {
+        Page::Query => view_page_query(model),
+        Page::Matrix => matrix::view_matrix(model),
+        Page::Projection => div![
+            view_query(model),
+            div![
+                style! {
+                    St::Display => "flex",
+                    St::FlexDirection => "row",
+                },
+                projection::view_projection(model),
+                detail::view_detail(model),
+            ],
+        ],
+        Page::Map => mapfile::view_map_file(model),
+        Page::Graph => div![view_query(model), graph::view_graph(model)],
+        Page::Sqrt => sqrtreport::view_sqrt_report(model),
+    };
+
+    div![view_page_select(model), view_speed(model), page]
+}
+
+fn view_page_select(model: &Model) -> Node<Msg> {
+    const ID_SELECT: &str = "select-language";
+
+    let t = model.text();
+
+    let buttons = Page::iter().map(|page| {
+        button![
+            attrs! {
+                At::Disabled => (page == model.page).as_at_value(),
+            },
+            page.label(t),
+            ev(Ev::Click, move |_| Msg::SetPage(page)),
+        ]
+    });
+
+    let options = Language::iter().map(|lang| {
+        option![
+            attrs! {
+                At::Value => lang.code(),
+                At::Selected => (lang == model.lang).as_at_value(),
+            },
+            lang.native_name(),
+        ]
+    });
+
+    p![
+        buttons,
+        " ",
+        button![t.reset_settings, ev(Ev::Click, |_| Msg::ResetSettings)],
+        " ",
+        label![
+            attrs! {
+                At::For => ID_SELECT,
+            },
+            format!("{}: ", t.language),
+        ],
+        select![
+            id!(ID_SELECT),
+            options,
+            input_ev(Ev::Change, |s| Language::from_code(&s)
+                .map(Msg::SetLanguage)),
+        ],
+    ]
+}
+
+fn view_page_query(model: &Model) -> Node<Msg> {
+    let ans = model.query.execute(&model.map, model.rules());
 
     div![
-        view_speed(model),
         view_query(model),
         div![
             style! {
                 St::Display => "flex",
                 St::FlexDirection => "row",
             },
-            view_map(model, &ans),
-            view_table(model, &ans),
+            div![
+                view_map(model, &ans),
+                viewport::view_controls(model),
+                playback::view_controls(model, &ans),
+                viewport::view_legend(model),
+            ],
+            div![
+                editor::view_editor(model),
+                detail::view_detail(model),
+                table::view_table(model, &ans),
+            ],
         ],
     ]
 }
 
+/// `content` を `filename` としてダウンロードさせるリンク。
+fn view_download(label: &str, filename: &str, mime: &str, content: &str) -> Node<Msg> {
+    let href = format!(
+        "data:{mime};charset=utf-8,{}",
+        js_sys::encode_uri_component(content)
+    );
+
+    a![
+        attrs! {
+            At::Href => href,
+            At::Download => filename,
+        },
+        label,
+    ]
+}
+
 fn view_speed(model: &Model) -> Node<Msg> {
     const ID_SELECT: &str = "select-speed";
+    const ID_CHECKBOX: &str = "checkbox-compare-speeds";
+
+    let t = model.text();
 
     let options = SPEEDS.iter().map(|&speed| {
         option![
@@ -113,13 +509,28 @@ fn view_speed(model: &Model) -> Node<Msg> {
             attrs! {
                 At::For => ID_SELECT,
             },
-            "移動力: ",
+            format!("{}: ", t.speed),
         ],
         select![
             id!(ID_SELECT),
             options,
             input_ev(Ev::Change, |s| s.parse::<u32>().ok().map(Msg::SetSpeed)),
         ],
+        " ",
+        input![
+            id!(ID_CHECKBOX),
+            attrs! {
+                At::Type => "checkbox",
+                At::Checked => model.compare_speeds.as_at_value(),
+            },
+            ev(Ev::Change, |_| Msg::ToggleCompareSpeeds),
+        ],
+        label![
+            attrs! {
+                At::For => ID_CHECKBOX,
+            },
+            t.compare_speeds,
+        ],
     ]
 }
 
@@ -127,8 +538,10 @@ fn view_query(model: &Model) -> Node<Msg> {
     const ID_SELECT: &str = "select-query";
     const VALUE_POSITION: usize = 999;
 
-    let options_planet = (0..PLANET_COUNT).map(|id| {
-        let name = planet_name(id);
+    let t = model.text();
+
+    let options_planet = (0..model.map.planet_count()).map(|id| {
+        let name = model.planet_name(id);
         option![
             attrs! {
                 At::Value => id,
@@ -141,14 +554,15 @@ fn view_query(model: &Model) -> Node<Msg> {
         attrs! {
             At::Value => VALUE_POSITION,
         },
-        "座標指定",
+        t.source_position,
     ];
 
     let input_args = match &model.query {
-        Query::Planet(q) => view_query_planet(q),
-        Query::Position(q) => view_query_position(q),
+        Query::Planet(q) => view_query_planet(t, q),
+        Query::Position(q) => view_query_position(t, q),
     };
 
+    let planet_count = model.map.planet_count();
     let value = match &model.query {
         Query::Planet(q) => q.src(),
         Query::Position(_) => VALUE_POSITION,
@@ -159,7 +573,7 @@ fn view_query(model: &Model) -> Node<Msg> {
             attrs! {
                 At::For => ID_SELECT,
             },
-            "始点: ",
+            format!("{}: ", t.source),
         ],
         select![
             id!(ID_SELECT),
@@ -170,17 +584,55 @@ fn view_query(model: &Model) -> Node<Msg> {
             option_pos,
             input_ev(Ev::Change, move |s| s.parse::<usize>().ok().and_then(
                 |x| match x {
-                    0..=PLANET_COUNT => Some(Msg::SetSourcePlanet(x)),
                     VALUE_POSITION => Some(Msg::SetSourcePosition),
+                    _ if x < planet_count => Some(Msg::SetSourcePlanet(x)),
                     _ => None,
                 }
             )),
         ],
         input_args,
+        view_planet_search(model),
     ]
 }
 
-fn view_query_planet(q: &QueryPlanet) -> Node<Msg> {
+/// 始点の惑星を名前で検索する入力欄と候補。Enter で先頭の候補を選ぶ。
+fn view_planet_search(model: &Model) -> Node<Msg> {
+    const MATCH_MAX: usize = 8;
+
+    let t = model.text();
+
+    let matches = search_planets(&model.map, &model.planet_search);
+    let first = matches.first().map(|m| m.id);
+
+    let buttons = matches.iter().take(MATCH_MAX).map(|m| {
+        let id = m.id;
+        button![
+            format!("{id}:{}", model.planet_name(id)),
+            ev(Ev::Click, move |_| Msg::SetSourcePlanet(id)),
+        ]
+    });
+
+    span![
+        " ",
+        input![
+            attrs! {
+                At::Type => "search",
+                At::Placeholder => t.search_planet,
+                At::Value => model.planet_search,
+            },
+            input_ev(Ev::Input, Msg::SetPlanetSearch),
+            keyboard_ev(Ev::KeyDown, move |ev| {
+                (ev.key() == "Enter")
+                    .then_some(first)
+                    .flatten()
+                    .map(Msg::SetSourcePlanet)
+            }),
+        ],
+        buttons,
+    ]
+}
+
+fn view_query_planet(t: &Text, q: &QueryPlanet) -> Node<Msg> {
     const ID_INPUT: &str = "input-query-planet-energy";
 
     span![
@@ -188,7 +640,7 @@ fn view_query_planet(q: &QueryPlanet) -> Node<Msg> {
             attrs! {
                 At::For => ID_INPUT,
             },
-            "エネルギー: "
+            format!("{}: ", t.energy),
         ],
         input![
             id!(ID_INPUT),
@@ -206,9 +658,9 @@ fn view_query_planet(q: &QueryPlanet) -> Node<Msg> {
     ]
 }
 
-fn view_query_position(q: &QueryPosition) -> Node<Msg> {
+fn view_query_position(t: &Text, q: &QueryPosition) -> Node<Msg> {
     span![
-        label!["座標: "],
+        label![format!("{}: ", t.position)],
         "(",
         view_position_component(q.src().x, Msg::SetQueryPositionX),
         ",",
@@ -219,6 +671,7 @@ fn view_query_position(q: &QueryPosition) -> Node<Msg> {
     ]
 }
 
+/// 座標の成分の入力欄。`max` 属性は直接入力した値を制限しないので、範囲外の値は `COORD_MAX` に丸める。
 fn view_position_component<F>(value: u32, f: F) -> Node<Msg>
 where
     F: FnOnce(u32) -> Msg + Clone + 'static,
@@ -227,53 +680,24 @@ where
         attrs! {
             At::Type => "number",
             At::Min => 0,
-            At::Max => 128,
+            At::Max => COORD_MAX,
             At::Value => value,
         },
-        input_ev(Ev::Change, |s| s.parse::<u32>().ok().map(f)),
-    ]
-}
-
-fn view_table(model: &Model, ans: &Answer) -> Node<Msg> {
-    let rows = (0..PLANET_COUNT)
-        .filter(|&dst| !planet_is_source(model, dst))
-        .flat_map(|dst| {
-            ans.cost(dst, model.speed).map(|(turn, energy)| {
-                let name = planet_name(dst);
-                let dist = model.query.src_pos().distance(planet_position(dst));
-                tr![
-                    td![format!("{dst}:{name}")],
-                    td![dist],
-                    td![turn],
-                    td![energy],
-                ]
-            })
-        });
-
-    div![
-        style! {
-            St::AlignSelf => "start",
-        },
-        table![
-            thead![tr![th!["目的地"], th!["距離"], th!["日数"], th!["消費"]],],
-            tbody![rows],
-        ],
+        input_ev(Ev::Change, |s| s
+            .parse::<u32>()
+            .ok()
+            .map(|c| f(c.min(COORD_MAX)))),
     ]
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-enum PlanetVolume {
-    Large,
-    Medium,
-    Small,
-    Tiny,
-}
-
 impl PlanetVolume {
-    fn iter() -> impl Iterator<Item = Self> + Clone {
-        use PlanetVolume::*;
-
-        [Large, Medium, Small, Tiny].into_iter()
+    fn label(self, t: &Text) -> &'static str {
+        match self {
+            Self::Large => t.volume_large,
+            Self::Medium => t.volume_medium,
+            Self::Small => t.volume_small,
+            Self::Tiny => t.volume_tiny,
+        }
     }
 }
 
@@ -293,9 +717,6 @@ impl PlanetColor {
 }
 
 fn view_map(model: &Model, ans: &Answer) -> Node<Msg> {
-    const WIDTH: u32 = 512;
-    const HEIGHT: u32 = 480;
-
     let defs_images =
         itertools::iproduct!(PlanetVolume::iter(), PlanetColor::iter()).map(|(volume, color)| {
             let image_id = planet_image_id(volume, color);
@@ -309,32 +730,54 @@ fn view_map(model: &Model, ans: &Answer) -> Node<Msg> {
             ]
         });
 
-    let planet_uses = (0..PLANET_COUNT).map(|id| {
-        let volume = planet_volume(id);
+    let map = &model.map;
+
+    // 隣接関係は双方向に登録されているので、片方向のみ描く。
+    let edges = map.edges().map(|(id, dst)| {
+        let (x1, y1) = map.planet(id).map_position();
+        let (x2, y2) = map.planet(dst).map_position();
+        let (stroke, width, opacity) = if ans.is_edge_searched(id, dst) {
+            ("orange", 3, 1.0)
+        } else {
+            ("gray", 1, 0.5)
+        };
+        line_![attrs! {
+            At::X1 => x1 + 16,
+            At::Y1 => y1 + 16,
+            At::X2 => x2 + 16,
+            At::Y2 => y2 + 16,
+            At::Stroke => stroke,
+            At::StrokeWidth => width,
+            At::Opacity => opacity,
+        }]
+    });
+
+    let planet_uses = map.planets().iter().enumerate().map(|(id, planet)| {
+        let volume = planet.volume();
         let color = match (planet_is_source(model, id), ans.is_reachable(id)) {
             (true, _) => PlanetColor::Source,
             (false, true) => PlanetColor::Reachable,
             (false, false) => PlanetColor::Unreachable,
         };
-        let (x, y) = planet_position_on_map(id);
+        let (x, y) = planet.map_position();
         r#use![
             attrs! {
                 At::Href => format!("#{}", planet_image_id(volume, color)),
                 At::X => x,
                 At::Y => y,
             },
-            ev(Ev::Click, move |_| Msg::SetSourcePlanet(id)),
+            editor::planet_event_handlers(model, id),
         ]
     });
 
-    let planet_turns = (0..PLANET_COUNT)
+    let planet_turns = (0..map.planet_count())
         .filter(|&dst| !planet_is_source(model, dst))
         .flat_map(|dst| {
             if planet_is_source(model, dst) {
                 return None;
             }
             ans.cost(dst, model.speed).map(|(turn, _)| {
-                let (px, py) = planet_position_on_map(dst);
+                let (px, py) = map.planet(dst).map_position();
                 let x = px + 12;
                 let y = py + 48;
                 text![
@@ -348,116 +791,54 @@ fn view_map(model: &Model, ans: &Answer) -> Node<Msg> {
             })
         });
 
+    let planet_labels = model.viewport.show_labels().then(|| {
+        (0..map.planet_count()).map(|id| {
+            let (x, y) = map.planet(id).map_position();
+            text![
+                C!["planet-label"],
+                attrs! {
+                    At::X => x + 32,
+                    At::Y => y + 12,
+                    At::Fill => "white",
+                    At::FontSize => 12,
+                },
+                model.planet_name(id),
+            ]
+        })
+    });
+
     svg![
         attrs! {
-            At::Width => WIDTH,
-            At::Height => HEIGHT,
-            At::ViewBox => format!("0 0 {WIDTH} {HEIGHT}"),
+            At::Width => MAP_WIDTH,
+            At::Height => MAP_HEIGHT,
+            At::ViewBox => model.viewport.view_box(),
+        },
+        style! {
+            St::TouchAction => "none",
         },
+        viewport::event_handlers(),
         defs![defs_images],
-        image![attrs! {
-            At::Href => "asset/map-background.png",
-            At::Width => WIDTH,
-            At::Height => HEIGHT,
-        }],
+        map.background().map(|href| image![attrs! {
+            At::Href => href,
+            At::Width => MAP_WIDTH,
+            At::Height => MAP_HEIGHT,
+        }]),
+        edges,
         planet_uses,
         planet_turns,
+        planet_labels.into_iter().flatten(),
+        playback::view_markers(model),
     ]
 }
 
 fn planet_image_id(volume: PlanetVolume, color: PlanetColor) -> String {
-    let s_volume = match volume {
-        PlanetVolume::Large => "large",
-        PlanetVolume::Medium => "medium",
-        PlanetVolume::Small => "small",
-        PlanetVolume::Tiny => "tiny",
-    };
-
     let s_color = match color {
         PlanetColor::Source => "blue",
         PlanetColor::Reachable => "red",
         PlanetColor::Unreachable => "gray",
     };
 
-    format!("planet-{s_volume}-{s_color}")
-}
-
-fn planet_volume(id: usize) -> PlanetVolume {
-    const TABLE: [PlanetVolume; PLANET_COUNT] = [
-        PlanetVolume::Large,
-        PlanetVolume::Large,
-        PlanetVolume::Large,
-        PlanetVolume::Medium,
-        PlanetVolume::Large,
-        PlanetVolume::Tiny,
-        PlanetVolume::Medium,
-        PlanetVolume::Medium,
-        PlanetVolume::Large,
-        PlanetVolume::Small,
-        PlanetVolume::Medium,
-        PlanetVolume::Large,
-        PlanetVolume::Tiny,
-        PlanetVolume::Tiny,
-        PlanetVolume::Large,
-        PlanetVolume::Large,
-        PlanetVolume::Tiny,
-        PlanetVolume::Small,
-        PlanetVolume::Medium,
-        PlanetVolume::Medium,
-        PlanetVolume::Tiny,
-        PlanetVolume::Small,
-        PlanetVolume::Medium,
-        PlanetVolume::Tiny,
-        PlanetVolume::Tiny,
-        PlanetVolume::Small,
-        PlanetVolume::Tiny,
-        PlanetVolume::Tiny,
-        PlanetVolume::Tiny,
-        PlanetVolume::Tiny,
-        PlanetVolume::Tiny,
-        PlanetVolume::Tiny,
-    ];
-
-    TABLE[id]
-}
-
-fn planet_position_on_map(id: usize) -> (u32, u32) {
-    const TABLE: [(u32, u32); PLANET_COUNT] = [
-        (56, 392),
-        (56, 344),
-        (104, 360),
-        (72, 296),
-        (152, 280),
-        (200, 216),
-        (104, 216),
-        (232, 168),
-        (280, 312),
-        (168, 168),
-        (232, 280),
-        (328, 248),
-        (56, 248),
-        (56, 184),
-        (424, 392),
-        (440, 296),
-        (184, 328),
-        (248, 40),
-        (376, 216),
-        (376, 168),
-        (248, 104),
-        (328, 168),
-        (392, 328),
-        (296, 120),
-        (312, 376),
-        (392, 120),
-        (360, 72),
-        (440, 360),
-        (376, 392),
-        (424, 232),
-        (424, 168),
-        (424, 40),
-    ];
-
-    TABLE[id]
+    format!("planet-{}-{s_color}", volume.key())
 }
 
 fn planet_is_source(model: &Model, id: usize) -> bool {
@@ -466,3 +847,57 @@ fn planet_is_source(model: &Model, id: usize) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(params: &[(&str, &str)]) -> Model {
+        let search = UrlSearch::new(params.iter().map(|&(key, value)| (key, [value])));
+        let mut model = Model::default();
+        model.load_search(&search);
+
+        model
+    }
+
+    fn params(model: &Model) -> Vec<(String, String)> {
+        model
+            .url_search()
+            .iter()
+            .flat_map(|(key, values)| values.iter().map(move |v| (key.clone(), v.clone())))
+            .collect()
+    }
+
+    #[test]
+    fn url_round_trip() {
+        for query in [
+            &[("speed", "20"), ("src", "5"), ("energy", "40")][..],
+            &[("speed", "10"), ("pos", "1,2,3")],
+        ] {
+            let model = load(query);
+            let mut expected: Vec<_> = query
+                .iter()
+                .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+                .collect();
+            expected.sort();
+            assert_eq!(params(&model), expected);
+        }
+    }
+
+    #[test]
+    fn url_values_are_clamped() {
+        let model = load(&[("speed", "30"), ("src", "0"), ("energy", "4000000000")]);
+        assert_eq!(
+            params(&model),
+            [("energy", "100"), ("speed", "30"), ("src", "0")]
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        );
+
+        let model = load(&[("speed", "30"), ("pos", "200,0,4000000000")]);
+        assert_eq!(
+            params(&model),
+            [("pos", "128,0,128"), ("speed", "30")]
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use seed::{prelude::*, *};
+
+use crate::query::Query;
+
+use super::{Model, Msg, SPEEDS};
+
+/// マップ上で選択した惑星の詳細情報。
+pub(super) fn view_detail(model: &Model) -> Node<Msg> {
+    let t = model.text();
+
+    let Some(id) = model.detail else {
+        return p![t.detail_hint];
+    };
+
+    let planet = model.map.planet(id);
+    let pos = planet.position();
+    let src_pos = model.query.src_pos(&model.map);
+    let rules = model.rules();
+
+    let neighbors = planet
+        .neighbors()
+        .iter()
+        .map(|&dst| format!("{dst}:{}", model.planet_name(dst)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // 始点が座標指定の場合、移動命令を出すのに必要なエネルギーという概念はない。
+    let energy_min = match &model.query {
+        Query::Planet(q) => rules
+            .min_energy_to_order(&model.map, q.src(), id)
+            .map_or_else(|| t.unreachable.to_owned(), |energy| energy.to_string()),
+        Query::Position(_) => "-".to_owned(),
+    };
+
+    let costs = SPEEDS.iter().map(|&speed| {
+        let (turn, energy) = rules.actual_turns_and_energy(src_pos, pos, speed);
+        tr![td![speed], td![turn], td![energy]]
+    });
+
+    div![
+        C!["detail"],
+        table![
+            tr![
+                th![t.planet],
+                td![format!("{id}:{}", model.planet_name(id))]
+            ],
+            tr![
+                th![t.coordinates],
+                td![format!("({}, {}, {})", pos.x, pos.y, pos.z)]
+            ],
+            tr![th![t.volume], td![planet.volume().label(t)]],
+            tr![th![t.neighbors], td![neighbors]],
+            tr![
+                th![t.distance_from_source],
+                td![rules.distance(src_pos, pos)]
+            ],
+            tr![th![t.energy_to_order], td![energy_min]],
+        ],
+        table![
+            thead![tr![th![t.speed], th![t.turns], th![t.consumption]]],
+            tbody![costs],
+        ],
+    ]
+}
@@ -0,0 +1,218 @@
+use seed::{prelude::*, *};
+
+use crate::geometry::Vec3;
+use crate::starmap::PLANET_IMAGE_SIZE;
+
+use super::viewport::ViewportMsg;
+use super::{mapfile, view_position_component, Model, Msg, MAP_HEIGHT, MAP_WIDTH};
+
+/// マップ編集の状態。
+#[derive(Debug, Default)]
+pub(super) struct Editor {
+    enabled: bool,
+    /// 座標と隣接関係を編集中の惑星。
+    selected: Option<usize>,
+    drag: Option<Drag>,
+}
+
+/// マップ上で惑星をドラッグ中の状態。
+#[derive(Debug)]
+struct Drag {
+    id: usize,
+    /// 直前のポインタ位置 (クライアント座標)。
+    pointer: (f64, f64),
+    /// 惑星画像の左上の位置 (マップ上のピクセル)。端数を保つため実数で持つ。
+    position: (f64, f64),
+}
+
+#[derive(Debug)]
+pub(super) enum EditorMsg {
+    Toggle,
+    Select(usize),
+    DragStart(usize, f64, f64),
+    SetPosition(Vec3),
+    Connect(usize),
+    Disconnect(usize),
+}
+
+pub(super) fn update(msg: EditorMsg, model: &mut Model) {
+    let editor = &mut model.editor;
+
+    match msg {
+        EditorMsg::Toggle => {
+            editor.enabled = !editor.enabled;
+            editor.drag = None;
+        }
+        EditorMsg::Select(id) => {
+            editor.selected = Some(id);
+            model.detail = Some(id);
+        }
+        EditorMsg::DragStart(id, x, y) => {
+            let (px, py) = model.map.planet(id).map_position();
+            editor.drag = Some(Drag {
+                id,
+                pointer: (x, y),
+                position: (f64::from(px), f64::from(py)),
+            });
+        }
+        EditorMsg::SetPosition(position) => {
+            if let Some(id) = editor.selected {
+                model.map.set_position(id, position);
+                mapfile::save_storage(model);
+            }
+        }
+        EditorMsg::Connect(dst) => {
+            if let Some(id) = editor.selected {
+                model.map.connect(id, dst);
+                mapfile::save_storage(model);
+            }
+        }
+        EditorMsg::Disconnect(dst) => {
+            if let Some(id) = editor.selected {
+                model.map.disconnect(id, dst);
+                mapfile::save_storage(model);
+            }
+        }
+    }
+}
+
+/// 惑星のドラッグ中ならポインタの移動を惑星の移動として扱う。
+/// メッセージを処理した場合、マップのパンには使わないので `true` を返す。
+pub(super) fn handle_pointer(msg: &ViewportMsg, model: &mut Model) -> bool {
+    let Some(drag) = &mut model.editor.drag else {
+        return false;
+    };
+
+    match *msg {
+        ViewportMsg::PointerMove(x, y) => {
+            // 拡大表示中はポインタの移動量よりマップ上の移動量が小さい。
+            let zoom = model.viewport.zoom();
+            let max_x = f64::from(MAP_WIDTH - PLANET_IMAGE_SIZE);
+            let max_y = f64::from(MAP_HEIGHT - PLANET_IMAGE_SIZE);
+            drag.position.0 = (drag.position.0 + (x - drag.pointer.0) / zoom).clamp(0.0, max_x);
+            drag.position.1 = (drag.position.1 + (y - drag.pointer.1) / zoom).clamp(0.0, max_y);
+            drag.pointer = (x, y);
+
+            let position = (
+                drag.position.0.round() as u32,
+                drag.position.1.round() as u32,
+            );
+            model.map.set_map_position(drag.id, position);
+            true
+        }
+        ViewportMsg::PointerUp => {
+            model.editor.drag = None;
+            mapfile::save_storage(model);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// マップ上の惑星に付けるイベントハンドラ。編集中はクリックで選択、ドラッグで移動する。
+pub(super) fn planet_event_handlers(model: &Model, id: usize) -> Vec<EventHandler<Msg>> {
+    if !model.editor.enabled {
+        return vec![
+            ev(Ev::Click, move |_| Msg::SetSourcePlanet(id)),
+            ev(Ev::MouseEnter, move |_| Msg::ShowPlanetDetail(id)),
+        ];
+    }
+
+    vec![
+        mouse_ev(Ev::MouseDown, move |ev| {
+            // マップのパンを始めないようにする。
+            ev.stop_propagation();
+            ev.prevent_default();
+            Msg::Editor(EditorMsg::DragStart(
+                id,
+                f64::from(ev.client_x()),
+                f64::from(ev.client_y()),
+            ))
+        }),
+        ev(Ev::Click, move |_| Msg::Editor(EditorMsg::Select(id))),
+        ev(Ev::MouseEnter, move |_| Msg::ShowPlanetDetail(id)),
+    ]
+}
+
+/// 編集の開始/終了ボタンと、選択中の惑星の座標・隣接関係の編集欄。
+pub(super) fn view_editor(model: &Model) -> Node<Msg> {
+    let t = model.text();
+    let editor = &model.editor;
+
+    let toggle = button![
+        if editor.enabled {
+            t.edit_done
+        } else {
+            t.edit_map
+        },
+        ev(Ev::Click, |_| Msg::Editor(EditorMsg::Toggle)),
+    ];
+
+    if !editor.enabled {
+        return p![toggle];
+    }
+
+    let Some(id) = editor.selected else {
+        return div![p![toggle, " ", t.edit_hint]];
+    };
+
+    let planet = model.map.planet(id);
+    let pos = planet.position();
+
+    let neighbors = planet.neighbors().iter().map(|&dst| {
+        li![
+            format!("{dst}:{} ", model.planet_name(dst)),
+            button![
+                "×",
+                ev(Ev::Click, move |_| Msg::Editor(EditorMsg::Disconnect(dst))),
+            ],
+        ]
+    });
+
+    let candidates = (0..model.map.planet_count())
+        .filter(|&dst| dst != id && !planet.neighbors().contains(&dst))
+        .map(|dst| {
+            option![
+                attrs! {
+                    At::Value => dst,
+                },
+                format!("{dst}:{}", model.planet_name(dst)),
+            ]
+        });
+
+    div![
+        p![toggle, " ", t.edit_hint],
+        p![
+            format!("{id}:{} ", model.planet_name(id)),
+            label![format!("{}: ", t.coordinates)],
+            "(",
+            view_position_component(pos.x, move |x| {
+                Msg::Editor(EditorMsg::SetPosition(Vec3 { x, ..pos }))
+            }),
+            ",",
+            view_position_component(pos.y, move |y| {
+                Msg::Editor(EditorMsg::SetPosition(Vec3 { y, ..pos }))
+            }),
+            ",",
+            view_position_component(pos.z, move |z| {
+                Msg::Editor(EditorMsg::SetPosition(Vec3 { z, ..pos }))
+            }),
+            ")",
+        ],
+        p![t.neighbors],
+        ul![neighbors],
+        p![select![
+            option![
+                attrs! {
+                    At::Value => "",
+                },
+                t.add_neighbor,
+            ],
+            candidates,
+            input_ev(Ev::Change, |s| s
+                .parse::<usize>()
+                .ok()
+                .map(|dst| Msg::Editor(EditorMsg::Connect(dst)))),
+        ]],
+    ]
+}
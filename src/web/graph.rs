@@ -0,0 +1,129 @@
+use seed::{prelude::*, *};
+
+use crate::graph::{self, GraphFormat};
+use crate::query::Query;
+
+use super::{view_download, Model, Msg};
+
+/// 隣接関係の解析結果。防衛上の要所 (関節点・橋) と、始点からのホップ数最小の経路を示す。
+pub(super) fn view_graph(model: &Model) -> Node<Msg> {
+    let t = model.text();
+    let map = &model.map;
+    let name = |id: usize| format!("{id}:{}", model.planet_name(id));
+    let names = |ids: &[usize]| {
+        if ids.is_empty() {
+            t.graph_none.to_owned()
+        } else {
+            ids.iter()
+                .map(|&id| name(id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+
+    let diameter = graph::diameter(map).map_or_else(
+        || "-".to_owned(),
+        |(hops, (src, dst))| format!("{hops} ({} - {})", name(src), name(dst)),
+    );
+
+    let bridges = graph::bridges(map);
+    let bridges = if bridges.is_empty() {
+        t.graph_none.to_owned()
+    } else {
+        bridges
+            .iter()
+            .map(|&(id1, id2)| format!("{} - {}", name(id1), name(id2)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    // 値の大きい順に並べる。
+    let mut betweenness: Vec<_> = graph::betweenness_centrality(map)
+        .into_iter()
+        .enumerate()
+        .collect();
+    betweenness.sort_by(|(_, c1), (_, c2)| c2.total_cmp(c1));
+    let betweenness_rows = betweenness
+        .iter()
+        .map(|&(id, c)| tr![td![name(id)], td![format!("{c:.1}")]]);
+
+    // 辺の実所要ターン数は選択中の移動力で計算する。
+    let downloads = GraphFormat::iter().map(|format| {
+        let filename = format!("star-map-{}.{}", model.speed, format.extension());
+        let content = graph::export(map, model.rules(), model.speed, format, model.lang);
+        span![
+            view_download(format.name(), &filename, format.mime(), &content),
+            " ",
+        ]
+    });
+
+    div![
+        p![downloads],
+        table![
+            tr![th![t.graph_diameter], td![diameter]],
+            tr![
+                th![t.graph_articulation_points],
+                td![names(&graph::articulation_points(map))]
+            ],
+            tr![th![t.graph_bridges], td![bridges]],
+        ],
+        div![
+            style! {
+                St::Display => "flex",
+                St::FlexDirection => "row",
+                St::AlignItems => "flex-start",
+            },
+            table![
+                thead![tr![th![t.planet], th![t.graph_betweenness]]],
+                tbody![betweenness_rows],
+            ],
+            view_hop_paths(model),
+        ],
+    ]
+}
+
+/// 始点の惑星から各惑星へのホップ数最小の経路の表。
+fn view_hop_paths(model: &Model) -> Node<Msg> {
+    let t = model.text();
+    let map = &model.map;
+
+    let Query::Planet(q) = &model.query else {
+        return p![t.graph_requires_planet];
+    };
+    let src = q.src();
+
+    let rows = (0..map.planet_count())
+        .filter(|&dst| dst != src)
+        .map(|dst| match graph::hop_path(map, src, dst) {
+            Some(path) => {
+                let names = path
+                    .iter()
+                    .map(|&id| model.planet_name(id))
+                    .collect::<Vec<_>>()
+                    .join(" → ");
+                tr![
+                    td![format!("{dst}:{}", model.planet_name(dst))],
+                    td![path.len() - 1],
+                    td![graph::path_distance(map, &path)],
+                    td![names],
+                ]
+            }
+            None => tr![
+                C!["unreachable"],
+                td![format!("{dst}:{}", model.planet_name(dst))],
+                td![t.unreachable],
+                td![],
+                td![],
+            ],
+        });
+
+    table![
+        thead![tr![
+            th![t.destination],
+            th![t.graph_hops],
+            th![t.distance],
+            th![t.graph_path],
+        ]],
+        tbody![rows],
+    ]
+}
@@ -0,0 +1,146 @@
+use seed::{prelude::*, *};
+
+use crate::starmap::{MapError, StarMap};
+use crate::validate::{self, Severity, Violation};
+
+use super::text::Text;
+use super::{view_download, Model, Msg};
+
+/// 読み込んだマップ定義を保存する localStorage のキー。組み込みのマップなら保存しない。
+pub(super) const STORAGE_KEY: &str = "logh-travel-map";
+
+/// マップ定義の読み込み欄の状態。
+#[derive(Debug, Default)]
+pub(super) struct MapFileState {
+    input: String,
+    error: Option<MapError>,
+}
+
+#[derive(Debug)]
+pub(super) enum MapFileMsg {
+    SetInput(String),
+    Load,
+    Reset,
+}
+
+pub(super) fn update(msg: MapFileMsg, model: &mut Model) {
+    match msg {
+        MapFileMsg::SetInput(s) => model.map_file.input = s,
+        MapFileMsg::Load => match StarMap::from_json(&model.map_file.input) {
+            Ok(map) => {
+                set_map(model, map);
+                model.map_file.error = None;
+                save_storage(model);
+            }
+            Err(e) => model.map_file.error = Some(e),
+        },
+        MapFileMsg::Reset => {
+            set_map(model, StarMap::builtin().clone());
+            model.map_file.error = None;
+            save_storage(model);
+        }
+    }
+}
+
+/// localStorage に保存されたマップ定義があれば反映する。
+pub(super) fn load_storage(model: &mut Model) {
+    let Ok(json) = LocalStorage::get::<_, String>(STORAGE_KEY) else {
+        return;
+    };
+    if let Ok(map) = StarMap::from_json(&json) {
+        set_map(model, map);
+    }
+}
+
+/// 現在のマップを localStorage に保存する。組み込みのマップと同じなら保存を消す。
+pub(super) fn save_storage(model: &Model) {
+    // 保存に失敗しても動作には影響しないので無視する。
+    if &model.map == StarMap::builtin() {
+        let _ = LocalStorage::remove(STORAGE_KEY);
+    } else {
+        let _ = LocalStorage::insert(STORAGE_KEY, &model.map.to_json());
+    }
+}
+
+/// マップを差し替える。惑星 ID を指す状態は新しいマップでは意味が変わるので初期化する。
+fn set_map(model: &mut Model, map: StarMap) {
+    model.map = map;
+    model.query = Default::default();
+    model.detail = None;
+    model.playback = Default::default();
+    model.editor = Default::default();
+}
+
+pub(super) fn view_map_file(model: &Model) -> Node<Msg> {
+    let t = model.text();
+    let state = &model.map_file;
+
+    let json = model.map.to_json();
+
+    // 読み込めたマップにも警告が残りうるので、現在のマップの検査結果を常に示す。
+    let violations = validate::validate(&model.map);
+    let report = if violations.is_empty() {
+        p![t.map_no_violations]
+    } else {
+        view_violations(model, &violations)
+    };
+
+    div![
+        p![format!(
+            "{}: {} ({})",
+            t.map_current,
+            model.map.name(),
+            model.map.planet_count()
+        )],
+        p![
+            view_download(t.map_download, "map.json", "application/json", &json),
+            " ",
+            button![
+                t.map_reset,
+                ev(Ev::Click, |_| Msg::MapFile(MapFileMsg::Reset)),
+            ],
+        ],
+        p![format!("{}:", t.map_violations)],
+        report,
+        p![t.map_load_hint],
+        textarea![
+            attrs! {
+                At::Rows => 16,
+                At::Cols => 80,
+                At::Value => state.input,
+            },
+            input_ev(Ev::Input, |s| Msg::MapFile(MapFileMsg::SetInput(s))),
+        ],
+        p![button![
+            t.map_load,
+            ev(Ev::Click, |_| Msg::MapFile(MapFileMsg::Load)),
+        ]],
+        state.error.as_ref().map(|e| match e {
+            MapError::Parse(e) => p![C!["error"], format!("{}: {e}", t.map_parse_error)],
+            MapError::Invalid(violations) => div![
+                C!["error"],
+                p![format!("{}:", t.map_invalid)],
+                view_violations(model, violations),
+            ],
+        }),
+    ]
+}
+
+fn view_violations(model: &Model, violations: &[Violation]) -> Node<Msg> {
+    let t = model.text();
+
+    ul![violations.iter().map(|v| {
+        li![format!(
+            "{}: {}",
+            severity_label(t, v.severity()),
+            v.message(model.lang)
+        )]
+    })]
+}
+
+fn severity_label(t: &Text, severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => t.severity_error,
+        Severity::Warning => t.severity_warning,
+    }
+}
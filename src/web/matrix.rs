@@ -0,0 +1,91 @@
+use seed::{prelude::*, *};
+
+use crate::matrix::TravelMatrix;
+
+use super::{view_download, Model, Msg, ENERGY_MAX};
+
+pub(super) fn view_matrix(model: &Model) -> Node<Msg> {
+    let matrix = TravelMatrix::new(&model.map, model.rules(), model.speed, model.matrix_energy);
+
+    div![
+        view_matrix_energy(model),
+        p![
+            view_download(
+                "CSV",
+                "travel-matrix.csv",
+                "text/csv",
+                &matrix.to_csv(model.lang)
+            ),
+            " ",
+            view_download(
+                "JSON",
+                "travel-matrix.json",
+                "application/json",
+                &matrix.to_json(model.lang)
+            ),
+        ],
+        view_matrix_table(model, &matrix),
+    ]
+}
+
+fn view_matrix_energy(model: &Model) -> Node<Msg> {
+    const ID_INPUT: &str = "input-matrix-energy";
+
+    p![
+        label![
+            attrs! {
+                At::For => ID_INPUT,
+            },
+            format!("{}: ", model.text().energy),
+        ],
+        input![
+            id!(ID_INPUT),
+            attrs! {
+                At::Type => "number",
+                At::Min => 0,
+                At::Max => ENERGY_MAX,
+                At::Value => model.matrix_energy,
+            },
+            input_ev(Ev::Change, |s| s
+                .parse::<u32>()
+                .ok()
+                .map(Msg::SetMatrixEnergy)),
+        ],
+    ]
+}
+
+/// 行が始点、列が目的地の表。各セルは "日数/消費" で、移動命令を出せないセルは網掛けする。
+fn view_matrix_table(model: &Model, matrix: &TravelMatrix) -> Node<Msg> {
+    let n = model.map.planet_count();
+
+    let header = (0..n).map(|dst| {
+        th![
+            attrs! {
+                At::Title => model.planet_name(dst),
+            },
+            dst,
+        ]
+    });
+
+    let rows = (0..n).map(|src| {
+        let cells = (0..n).map(|dst| {
+            if src == dst {
+                return td![];
+            }
+            let (turn, energy) = matrix.cost(src, dst);
+            td![
+                C![(!matrix.is_reachable(src, dst)).then_some("unreachable")],
+                format!("{turn}/{energy}"),
+            ]
+        });
+
+        let name = model.planet_name(src);
+        tr![th![format!("{src}:{name}")], cells]
+    });
+
+    table![
+        C!["matrix"],
+        thead![tr![th![model.text().matrix_corner], header]],
+        tbody![rows],
+    ]
+}
@@ -0,0 +1,257 @@
+use seed::{prelude::*, *};
+
+use crate::geometry::Vec3;
+use crate::query::{Answer, Query};
+use crate::starmap::StarMap;
+
+use super::{Model, Msg};
+
+/// 自動再生時の 1 ターンあたりの時間 (ms)。
+const INTERVAL: u32 = 500;
+
+/// 始点から目的地への艦隊の移動をターンごとに再生する状態。
+#[derive(Debug, Default)]
+pub(super) struct Playback {
+    dst: Option<usize>,
+    turn: usize,
+    stream: Option<StreamHandle>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum PlaybackMsg {
+    SetTarget(Option<usize>),
+    Play,
+    Pause,
+    Step,
+    Seek(usize),
+    Tick,
+}
+
+pub(super) fn update(msg: PlaybackMsg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    let turn_max = fleet_path(model).map_or(0, |path| path.len() - 1);
+    let playback = &mut model.playback;
+
+    match msg {
+        PlaybackMsg::SetTarget(dst) => {
+            playback.dst = dst;
+            playback.turn = 0;
+            playback.stream = None;
+        }
+        PlaybackMsg::Play => {
+            if playback.turn >= turn_max {
+                playback.turn = 0;
+            }
+            playback.stream = Some(orders.stream_with_handle(streams::interval(INTERVAL, || {
+                Msg::Playback(PlaybackMsg::Tick)
+            })));
+        }
+        PlaybackMsg::Pause => playback.stream = None,
+        PlaybackMsg::Step => {
+            playback.stream = None;
+            playback.turn = (playback.turn + 1).min(turn_max);
+        }
+        PlaybackMsg::Seek(turn) => {
+            playback.stream = None;
+            playback.turn = turn.min(turn_max);
+        }
+        PlaybackMsg::Tick => {
+            playback.turn += 1;
+            if playback.turn >= turn_max {
+                playback.turn = turn_max;
+                playback.stream = None;
+            }
+        }
+    }
+}
+
+/// 再生対象の経路を返す。始点が惑星でないか、目的地が未選択なら `None` を返す。
+fn fleet_path(model: &Model) -> Option<Vec<Vec3>> {
+    let Query::Planet(q) = &model.query else {
+        return None;
+    };
+    let dst = model.playback.dst.filter(|&dst| dst != q.src())?;
+
+    let position = |id| model.map.planet(id).position();
+    Some(
+        model
+            .rules()
+            .fleet_path(position(q.src()), position(dst), model.speed),
+    )
+}
+
+/// 艦隊の座標をマップ上のピクセル位置 (惑星画像の中心) に射影する。
+///
+/// マップ上の惑星の配置は座標からの単純な射影ではないので、始点と目的地の惑星のマップ上の位置の間を補間する。
+/// マップの横方向はおおむね x 座標、縦方向はおおむね z 座標に対応するので、
+/// 横方向は x 座標、縦方向は z 座標の進み具合で補間する。
+/// これにより、各方向の移動量の切り捨てによる経路の曲がりがマップ上でも見える。
+fn project(map: &StarMap, src: usize, dst: usize, p: Vec3) -> (f64, f64) {
+    let src_pos = map.planet(src).position();
+    let dst_pos = map.planet(dst).position();
+    let (src_x, src_y) = planet_center_on_map(map, src);
+    let (dst_x, dst_y) = planet_center_on_map(map, dst);
+
+    // 座標が変化しない方向については、全体の進み具合で代用する。
+    // 始点と目的地が同じ座標なら (検証では警告のみ) 着いているものとする。
+    let total = src_pos.distance(dst_pos);
+    let progress_total = if total == 0 {
+        1.0
+    } else {
+        1.0 - f64::from(p.distance(dst_pos)) / f64::from(total)
+    };
+    let progress = |a0: u32, a1: u32, a: u32| {
+        if a0 == a1 {
+            progress_total
+        } else {
+            f64::from(a0.abs_diff(a)) / f64::from(a0.abs_diff(a1))
+        }
+    };
+
+    let x = src_x + (dst_x - src_x) * progress(src_pos.x, dst_pos.x, p.x);
+    let y = src_y + (dst_y - src_y) * progress(src_pos.z, dst_pos.z, p.z);
+
+    (x, y)
+}
+
+fn planet_center_on_map(map: &StarMap, id: usize) -> (f64, f64) {
+    let (x, y) = map.planet(id).map_position();
+
+    (f64::from(x + 16), f64::from(y + 16))
+}
+
+/// マップに重ねる、現ターンまでの艦隊の軌跡とマーカー。
+pub(super) fn view_markers(model: &Model) -> Vec<Node<Msg>> {
+    let (Query::Planet(q), Some(path)) = (&model.query, fleet_path(model)) else {
+        return vec![];
+    };
+    let (src, dst) = (q.src(), model.playback.dst.unwrap());
+    let turn = model.playback.turn.min(path.len() - 1);
+
+    let points: Vec<_> = path[..=turn]
+        .iter()
+        .map(|&p| project(&model.map, src, dst, p))
+        .collect();
+
+    let trail = polyline![attrs! {
+        At::Points => points.iter().map(|(x, y)| format!("{x},{y}")).collect::<Vec<_>>().join(" "),
+        At::Fill => "none",
+        At::Stroke => "cyan",
+        At::StrokeWidth => 2,
+    }];
+
+    let dots = points.iter().map(|&(x, y)| {
+        circle![attrs! {
+            At::Cx => x,
+            At::Cy => y,
+            At::R => 3,
+            At::Fill => "cyan",
+        }]
+    });
+
+    let (x, y) = points[turn];
+    let marker = circle![attrs! {
+        At::Cx => x,
+        At::Cy => y,
+        At::R => 7,
+        At::Fill => "none",
+        At::Stroke => "white",
+        At::StrokeWidth => 2,
+    }];
+
+    let mut nodes = vec![trail];
+    nodes.extend(dots);
+    nodes.push(marker);
+
+    nodes
+}
+
+/// 再生する目的地の選択と、再生/一時停止/コマ送り/シークの操作。
+pub(super) fn view_controls(model: &Model, ans: &Answer) -> Node<Msg> {
+    const ID_SELECT: &str = "select-playback-target";
+    const VALUE_NONE: usize = 999;
+
+    let t = model.text();
+
+    let Query::Planet(q) = &model.query else {
+        return p![t.playback_requires_planet];
+    };
+
+    let planet_count = model.map.planet_count();
+    let options = (0..planet_count)
+        .filter(|&dst| dst != q.src() && ans.is_reachable(dst))
+        .map(|dst| {
+            let name = model.planet_name(dst);
+            option![
+                attrs! {
+                    At::Value => dst,
+                    At::Selected => (Some(dst) == model.playback.dst).as_at_value(),
+                },
+                format!("{dst}:{name}"),
+            ]
+        });
+
+    let select = select![
+        id!(ID_SELECT),
+        option![
+            attrs! {
+                At::Value => VALUE_NONE,
+            },
+            "-",
+        ],
+        options,
+        input_ev(Ev::Change, move |s| {
+            let dst = s.parse::<usize>().ok().filter(|&dst| dst < planet_count);
+            Msg::Playback(PlaybackMsg::SetTarget(dst))
+        }),
+    ];
+
+    let controls = fleet_path(model).map(|path| {
+        let turn_max = path.len() - 1;
+        let turn = model.playback.turn.min(turn_max);
+        let (button_label, button_msg) = if model.playback.stream.is_some() {
+            (t.pause, PlaybackMsg::Pause)
+        } else {
+            (t.play, PlaybackMsg::Play)
+        };
+
+        span![
+            " ",
+            button![
+                button_label,
+                ev(Ev::Click, move |_| Msg::Playback(button_msg))
+            ],
+            button![
+                attrs! {
+                    At::Disabled => (turn == turn_max).as_at_value(),
+                },
+                t.step,
+                ev(Ev::Click, |_| Msg::Playback(PlaybackMsg::Step)),
+            ],
+            " ",
+            input![
+                attrs! {
+                    At::Type => "range",
+                    At::Min => 0,
+                    At::Max => turn_max,
+                    At::Value => turn,
+                },
+                input_ev(Ev::Input, |s| s
+                    .parse::<usize>()
+                    .ok()
+                    .map(|turn| Msg::Playback(PlaybackMsg::Seek(turn)))),
+            ],
+            format!(" {turn}/{turn_max} {}", t.turns_unit),
+        ]
+    });
+
+    p![
+        label![
+            attrs! {
+                At::For => ID_SELECT,
+            },
+            format!("{}: ", t.playback),
+        ],
+        select,
+        controls,
+    ]
+}
@@ -0,0 +1,217 @@
+use seed::{prelude::*, *};
+
+use crate::geometry::Vec3;
+use crate::query::{Answer, Query};
+use crate::starmap::COORD_MAX;
+
+use super::text::Text;
+use super::{planet_is_source, Model, Msg, PlanetVolume};
+
+/// 描画領域の一辺の長さ (ピクセル)。
+const SIZE: u32 = 480;
+
+/// 3 次元座標を 2 次元に射影する方法。
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub(super) enum Projection {
+    #[default]
+    Xy,
+    Xz,
+    Yz,
+    Isometric,
+}
+
+impl Projection {
+    pub(super) fn iter() -> impl Iterator<Item = Self> + Clone {
+        use Projection::*;
+
+        [Xy, Xz, Yz, Isometric].into_iter()
+    }
+
+    pub(super) fn key(self) -> &'static str {
+        match self {
+            Self::Xy => "xy",
+            Self::Xz => "xz",
+            Self::Yz => "yz",
+            Self::Isometric => "iso",
+        }
+    }
+
+    fn label(self, t: &Text) -> &'static str {
+        match self {
+            Self::Xy => t.projection_xy,
+            Self::Xz => t.projection_xz,
+            Self::Yz => t.projection_yz,
+            Self::Isometric => t.projection_isometric,
+        }
+    }
+
+    /// 座標を (横, 縦) に射影する。縦は上向きを正とする。
+    fn project(self, p: Vec3) -> (f64, f64) {
+        let (x, y, z) = (f64::from(p.x), f64::from(p.y), f64::from(p.z));
+
+        match self {
+            Self::Xy => (x, y),
+            Self::Xz => (x, z),
+            Self::Yz => (y, z),
+            Self::Isometric => {
+                let (sin, cos) = 30_f64.to_radians().sin_cos();
+                ((x - y) * cos, z - (x + y) * sin)
+            }
+        }
+    }
+}
+
+/// 座標の存在しうる範囲を表す立方体の辺。
+fn cube_edges() -> impl Iterator<Item = (Vec3, Vec3)> {
+    let corners: Vec<_> = itertools::iproduct!([0, COORD_MAX], [0, COORD_MAX], [0, COORD_MAX])
+        .map(|(x, y, z)| Vec3::new(x, y, z))
+        .collect();
+
+    // 1 成分のみ異なる頂点の組が辺となる。
+    itertools::iproduct!(corners.clone(), corners).filter(|&(p, q)| {
+        let diffs = [p.x != q.x, p.y != q.y, p.z != q.z];
+        diffs.iter().filter(|&&d| d).count() == 1 && (p.x, p.y, p.z) < (q.x, q.y, q.z)
+    })
+}
+
+pub(super) fn view_projection(model: &Model) -> Node<Msg> {
+    let ans = model.query.execute(&model.map, model.rules());
+
+    div![
+        view_projection_select(model),
+        view_projection_svg(model, &ans),
+    ]
+}
+
+fn view_projection_select(model: &Model) -> Node<Msg> {
+    let buttons = Projection::iter().map(|projection| {
+        button![
+            attrs! {
+                At::Disabled => (projection == model.projection).as_at_value(),
+            },
+            projection.label(model.text()),
+            ev(Ev::Click, move |_| Msg::SetProjection(projection)),
+        ]
+    });
+
+    p![buttons]
+}
+
+/// 実際の座標に基づいて惑星と隣接関係を描く。
+fn view_projection_svg(model: &Model, ans: &Answer) -> Node<Msg> {
+    const MARGIN: f64 = 8.0;
+
+    let projection = model.projection;
+
+    // 立方体全体が収まるように縮尺を決める。
+    let corners: Vec<_> = cube_edges()
+        .flat_map(|(p, q)| [projection.project(p), projection.project(q)])
+        .collect();
+    let bounds = |values: &mut dyn Iterator<Item = f64>| {
+        values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| {
+            (lo.min(x), hi.max(x))
+        })
+    };
+    let (u_min, u_max) = bounds(&mut corners.iter().map(|&(u, _)| u));
+    let (v_min, v_max) = bounds(&mut corners.iter().map(|&(_, v)| v));
+    let scale = (f64::from(SIZE) - 2.0 * MARGIN) / (u_max - u_min).max(v_max - v_min);
+    let to_svg = |p: Vec3| {
+        let (u, v) = projection.project(p);
+        (MARGIN + (u - u_min) * scale, MARGIN + (v_max - v) * scale)
+    };
+
+    let cube = cube_edges().map(|(p, q)| {
+        let (x1, y1) = to_svg(p);
+        let (x2, y2) = to_svg(q);
+        line_![attrs! {
+            At::X1 => x1,
+            At::Y1 => y1,
+            At::X2 => x2,
+            At::Y2 => y2,
+            At::Stroke => "#444444",
+        }]
+    });
+
+    let map = &model.map;
+
+    let edges = map.edges().map(|(id, dst)| {
+        let (x1, y1) = to_svg(map.planet(id).position());
+        let (x2, y2) = to_svg(map.planet(dst).position());
+        line_![attrs! {
+            At::X1 => x1,
+            At::Y1 => y1,
+            At::X2 => x2,
+            At::Y2 => y2,
+            At::Stroke => "gray",
+            At::Opacity => 0.5,
+        }]
+    });
+
+    let planets = map.planets().iter().enumerate().map(|(id, planet)| {
+        let (x, y) = to_svg(planet.position());
+        let r = match planet.volume() {
+            PlanetVolume::Large => 8,
+            PlanetVolume::Medium => 6,
+            PlanetVolume::Small => 5,
+            PlanetVolume::Tiny => 4,
+        };
+        let fill = match (planet_is_source(model, id), ans.is_reachable(id)) {
+            (true, _) => "deepskyblue",
+            (false, true) => "red",
+            (false, false) => "gray",
+        };
+        g![
+            circle![
+                attrs! {
+                    At::Cx => x,
+                    At::Cy => y,
+                    At::R => r,
+                    At::Fill => fill,
+                },
+                title![format!("{id}:{}", model.planet_name(id))],
+            ],
+            text![
+                attrs! {
+                    At::X => x + f64::from(r),
+                    At::Y => y - f64::from(r),
+                    At::Fill => "white",
+                    At::FontSize => 10,
+                },
+                id,
+            ],
+            ev(Ev::Click, move |_| Msg::SetSourcePlanet(id)),
+            ev(Ev::MouseEnter, move |_| Msg::ShowPlanetDetail(id)),
+        ]
+    });
+
+    // 座標指定の始点は惑星と別に描く。
+    let src_position = match &model.query {
+        Query::Position(q) => {
+            let (x, y) = to_svg(q.src());
+            Some(circle![attrs! {
+                At::Cx => x,
+                At::Cy => y,
+                At::R => 5,
+                At::Fill => "none",
+                At::Stroke => "deepskyblue",
+                At::StrokeWidth => 2,
+            }])
+        }
+        Query::Planet(_) => None,
+    };
+
+    svg![
+        attrs! {
+            At::Width => SIZE,
+            At::Height => SIZE,
+            At::ViewBox => format!("0 0 {SIZE} {SIZE}"),
+        },
+        style! {
+            St::BackgroundColor => "black",
+        },
+        cube,
+        edges,
+        planets,
+        src_position,
+    ]
+}
@@ -0,0 +1,115 @@
+use std::sync::OnceLock;
+
+use seed::{prelude::*, *};
+
+use crate::export::Format;
+use crate::sqrtreport::{self, DeltaDisagreement, PlanetPairChange};
+use crate::starmap::StarMap;
+
+use super::{view_download, Model, Msg, Page};
+
+/// 座標の差を調べる範囲。座標の入力欄の上限と同じ。
+const MAX_DELTA: u32 = 128;
+
+/// 計算済みの報告。計算に時間がかかるので、マップや移動力が変わったときだけ計算し直す。
+#[derive(Debug)]
+pub(super) struct SqrtReport {
+    map: StarMap,
+    speed: u32,
+    travel_changes: Vec<PlanetPairChange<(u32, u32)>>,
+    order_changes: Vec<PlanetPairChange<Option<u32>>>,
+}
+
+/// 座標の差についての報告とその CSV。マップに依らないので一度だけ計算する。
+fn deltas() -> &'static (Vec<DeltaDisagreement>, String) {
+    static DELTAS: OnceLock<(Vec<DeltaDisagreement>, String)> = OnceLock::new();
+
+    DELTAS.get_or_init(|| {
+        let deltas = sqrtreport::delta_disagreements(MAX_DELTA);
+        let csv = sqrtreport::delta_table(&deltas).to_string(Format::Csv);
+        (deltas, csv)
+    })
+}
+
+/// 報告のページを表示していて、報告がマップや移動力に合っていなければ計算し直す。
+pub(super) fn refresh(model: &mut Model) {
+    if model.page != Page::Sqrt {
+        return;
+    }
+
+    match &mut model.sqrt_report {
+        Some(report) if report.map == model.map => {
+            if report.speed != model.speed {
+                report.speed = model.speed;
+                report.travel_changes = sqrtreport::travel_changes(&model.map, model.speed);
+            }
+        }
+        _ => {
+            model.sqrt_report = Some(SqrtReport {
+                map: model.map.clone(),
+                speed: model.speed,
+                travel_changes: sqrtreport::travel_changes(&model.map, model.speed),
+                order_changes: sqrtreport::order_changes(&model.map),
+            });
+        }
+    }
+}
+
+/// 平方根の計算方法によって結果が変わる箇所の報告。
+pub(super) fn view_sqrt_report(model: &Model) -> Node<Msg> {
+    let t = model.text();
+    let name = |id: usize| format!("{id}:{}", model.planet_name(id));
+
+    // `refresh` で計算しておく。
+    let Some(report) = &model.sqrt_report else {
+        return empty![];
+    };
+
+    let (deltas, deltas_csv) = deltas();
+
+    let travel_changes = &report.travel_changes;
+    let travel_csv =
+        sqrtreport::travel_table(&model.map, travel_changes, model.lang).to_string(Format::Csv);
+    let travel_rows = travel_changes.iter().map(|c| {
+        tr![
+            td![name(c.src)],
+            td![name(c.dst)],
+            td![format!("{}/{}", c.exact.0, c.exact.1)],
+            td![format!("{}/{}", c.rounded.0, c.rounded.1)],
+        ]
+    });
+
+    let energy = |e: Option<u32>| e.map_or_else(|| t.unreachable.to_owned(), |e| e.to_string());
+    let order_rows = report.order_changes.iter().map(|c| {
+        tr![
+            td![name(c.src)],
+            td![name(c.dst)],
+            td![energy(c.exact)],
+            td![energy(c.rounded)],
+        ]
+    });
+
+    let header = || {
+        thead![tr![
+            th![t.source],
+            th![t.destination],
+            th![t.sqrt_exact],
+            th![t.sqrt_rounded],
+        ]]
+    };
+
+    div![
+        p![t.sqrt_note],
+        p![
+            format!("{}: {} ", t.sqrt_deltas, deltas.len()),
+            view_download("CSV", "sqrt-deltas.csv", Format::Csv.mime(), deltas_csv),
+        ],
+        p![
+            format!("{}: {} ", t.sqrt_travel_changes, travel_changes.len()),
+            view_download("CSV", "sqrt-travel.csv", Format::Csv.mime(), &travel_csv),
+        ],
+        table![header(), tbody![travel_rows]],
+        p![t.sqrt_order_changes],
+        table![header(), tbody![order_rows]],
+    ]
+}
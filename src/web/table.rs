@@ -0,0 +1,375 @@
+use std::cmp::Ordering;
+
+use seed::{prelude::*, *};
+
+use crate::export::Format;
+use crate::locale::Language;
+use crate::query::Answer;
+
+use super::text::Text;
+use super::{planet_is_source, view_download, Model, Msg, SPEEDS};
+
+/// 結果の表の並べ替え・絞り込みの状態。
+#[derive(Debug)]
+pub(super) struct TableState {
+    sort_key: SortKey,
+    descending: bool,
+    name_filter: String,
+    turn_min: Option<u32>,
+    turn_max: Option<u32>,
+    hide_unreachable: bool,
+}
+
+impl Default for TableState {
+    fn default() -> Self {
+        Self {
+            sort_key: SortKey::Id,
+            descending: false,
+            name_filter: String::new(),
+            turn_min: None,
+            turn_max: None,
+            hide_unreachable: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(super) enum SortKey {
+    Id,
+    Distance,
+    Turns,
+    Energy,
+}
+
+impl SortKey {
+    fn iter() -> impl Iterator<Item = Self> + Clone {
+        use SortKey::*;
+
+        [Id, Distance, Turns, Energy].into_iter()
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Distance => "distance",
+            Self::Turns => "turns",
+            Self::Energy => "energy",
+        }
+    }
+
+    fn label(self, t: &Text) -> &'static str {
+        match self {
+            Self::Id => t.destination,
+            Self::Distance => t.distance,
+            Self::Turns => t.turns,
+            Self::Energy => t.consumption,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) enum TableMsg {
+    /// 指定した列で並べ替える。既にその列で並べ替えていれば昇順/降順を切り替える。
+    SortBy(SortKey),
+    SetNameFilter(String),
+    SetTurnMin(Option<u32>),
+    SetTurnMax(Option<u32>),
+    ToggleHideUnreachable,
+}
+
+pub(super) fn update(msg: TableMsg, state: &mut TableState) {
+    match msg {
+        TableMsg::SortBy(key) => {
+            if state.sort_key == key {
+                state.descending = !state.descending;
+            } else {
+                state.sort_key = key;
+                state.descending = false;
+            }
+        }
+        TableMsg::SetNameFilter(s) => state.name_filter = s,
+        TableMsg::SetTurnMin(turn) => state.turn_min = turn,
+        TableMsg::SetTurnMax(turn) => state.turn_max = turn,
+        TableMsg::ToggleHideUnreachable => state.hide_unreachable = !state.hide_unreachable,
+    }
+}
+
+/// 表の 1 行。`cost` は選択中の移動力についての (日数, 消費) で、到達不能なら `None`。
+#[derive(Debug)]
+struct Row {
+    dst: usize,
+    dist: u32,
+    cost: Option<(u32, u32)>,
+}
+
+/// 絞り込みと並べ替えを適用した行を返す。
+fn rows(model: &Model, ans: &Answer) -> Vec<Row> {
+    let state = &model.table;
+
+    // 名前は表示言語に関わらず全ての言語の名前と照合する。
+    let mut rows: Vec<_> = (0..model.map.planet_count())
+        .filter(|&dst| !planet_is_source(model, dst))
+        .map(|dst| Row {
+            dst,
+            dist: model.rules().distance(
+                model.query.src_pos(&model.map),
+                model.map.planet(dst).position(),
+            ),
+            cost: ans.cost(dst, model.speed),
+        })
+        .filter(|row| !(state.hide_unreachable && row.cost.is_none()))
+        .filter(|row| {
+            let turn = row.cost.map(|(turn, _)| turn);
+            let ok_min = state
+                .turn_min
+                .is_none_or(|min| turn.is_some_and(|t| t >= min));
+            let ok_max = state
+                .turn_max
+                .is_none_or(|max| turn.is_some_and(|t| t <= max));
+            ok_min && ok_max
+        })
+        .filter(|row| {
+            let filter = state.name_filter.trim().to_lowercase();
+            filter.is_empty()
+                || Language::iter().any(|lang| {
+                    model
+                        .map
+                        .planet(row.dst)
+                        .name(lang)
+                        .to_lowercase()
+                        .contains(&filter)
+                })
+                || row.dst.to_string() == filter
+        })
+        .collect();
+
+    // 到達不能な行は並べ替えの向きに関わらず末尾に置く。
+    let sort_value = |row: &Row| match state.sort_key {
+        SortKey::Id => Some(row.dst as u32),
+        SortKey::Distance => Some(row.dist),
+        SortKey::Turns => row.cost.map(|(turn, _)| turn),
+        SortKey::Energy => row.cost.map(|(_, energy)| energy),
+    };
+    rows.sort_by(|a, b| match (sort_value(a), sort_value(b)) {
+        (Some(x), Some(y)) if state.descending => y.cmp(&x),
+        (Some(x), Some(y)) => x.cmp(&y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+
+    rows
+}
+
+pub(super) fn view_table(model: &Model, ans: &Answer) -> Node<Msg> {
+    let rows = rows(model, ans);
+
+    let table = if model.compare_speeds {
+        view_table_compare(model, ans, &rows)
+    } else {
+        view_table_single(model, &rows)
+    };
+
+    let downloads = Format::iter().map(|format| {
+        let filename = format!("travel.{}", format.extension());
+        let content = ans.export(model.speed, format, model.lang);
+        span![
+            view_download(format.name(), &filename, format.mime(), &content),
+            " ",
+        ]
+    });
+
+    div![
+        style! {
+            St::AlignSelf => "start",
+        },
+        p![downloads],
+        view_filter(model),
+        table,
+    ]
+}
+
+fn view_filter(model: &Model) -> Node<Msg> {
+    const ID_SORT: &str = "select-table-sort";
+    const ID_NAME: &str = "input-table-name";
+    const ID_HIDE: &str = "checkbox-table-hide-unreachable";
+
+    let t = model.text();
+    let state = &model.table;
+
+    let options = SortKey::iter().map(|key| {
+        option![
+            attrs! {
+                At::Value => key.key(),
+                At::Selected => (key == state.sort_key).as_at_value(),
+            },
+            key.label(t),
+        ]
+    });
+
+    let turn_input = |value: Option<u32>, f: fn(Option<u32>) -> TableMsg| {
+        input![
+            attrs! {
+                At::Type => "number",
+                At::Min => 0,
+                At::Value => value.map_or_else(String::new, |x| x.to_string()),
+            },
+            style! {
+                St::Width => em(4),
+            },
+            input_ev(Ev::Change, move |s| Msg::Table(f(s.parse::<u32>().ok()))),
+        ]
+    };
+
+    div![
+        p![
+            label![attrs! { At::For => ID_SORT }, format!("{}: ", t.sort)],
+            select![
+                id!(ID_SORT),
+                options,
+                input_ev(Ev::Change, |s| SortKey::iter()
+                    .find(|key| key.key() == s)
+                    .map(|key| Msg::Table(TableMsg::SortBy(key)))),
+            ],
+            button![
+                if state.descending {
+                    t.descending
+                } else {
+                    t.ascending
+                },
+                ev(Ev::Click, {
+                    let key = state.sort_key;
+                    move |_| Msg::Table(TableMsg::SortBy(key))
+                }),
+            ],
+        ],
+        p![
+            label![attrs! { At::For => ID_NAME }, format!("{}: ", t.name)],
+            input![
+                id!(ID_NAME),
+                attrs! {
+                    At::Type => "search",
+                    At::Value => state.name_filter,
+                },
+                input_ev(Ev::Input, |s| Msg::Table(TableMsg::SetNameFilter(s))),
+            ],
+        ],
+        p![
+            format!("{}: ", t.turns),
+            turn_input(state.turn_min, TableMsg::SetTurnMin),
+            " ～ ",
+            turn_input(state.turn_max, TableMsg::SetTurnMax),
+            " ",
+            input![
+                id!(ID_HIDE),
+                attrs! {
+                    At::Type => "checkbox",
+                    At::Checked => state.hide_unreachable.as_at_value(),
+                },
+                ev(Ev::Change, |_| Msg::Table(TableMsg::ToggleHideUnreachable)),
+            ],
+            label![attrs! { At::For => ID_HIDE }, t.hide_unreachable],
+        ],
+    ]
+}
+
+/// クリックで並べ替えられる列見出し。
+fn view_sort_header(model: &Model, key: SortKey, rowspan: u32) -> Node<Msg> {
+    let state = &model.table;
+    let mark = match (state.sort_key == key, state.descending) {
+        (false, _) => "",
+        (true, false) => "▲",
+        (true, true) => "▼",
+    };
+
+    th![
+        C!["sortable"],
+        attrs! {
+            At::RowSpan => rowspan,
+        },
+        format!("{}{mark}", key.label(model.text())),
+        ev(Ev::Click, move |_| Msg::Table(TableMsg::SortBy(key))),
+    ]
+}
+
+fn view_table_single(model: &Model, rows: &[Row]) -> Node<Msg> {
+    let rows = rows.iter().map(|row| {
+        let name = model.planet_name(row.dst);
+        let (turn, energy) = match row.cost {
+            Some((turn, energy)) => (turn.to_string(), energy.to_string()),
+            None => ("-".to_owned(), "-".to_owned()),
+        };
+        tr![
+            C![row.cost.is_none().then_some("unreachable")],
+            td![format!("{}:{name}", row.dst)],
+            td![row.dist],
+            td![turn],
+            td![energy],
+        ]
+    });
+
+    let header = SortKey::iter().map(|key| view_sort_header(model, key, 1));
+
+    table![thead![tr![header]], tbody![rows],]
+}
+
+/// 全ての移動力についての (日数, 消費) を横に並べた表。
+/// 各行で日数が最小のものを強調する。日数と消費による並べ替えは選択中の移動力についてのものとする。
+fn view_table_compare(model: &Model, ans: &Answer, rows: &[Row]) -> Node<Msg> {
+    let t = model.text();
+
+    let rows = rows.iter().map(|row| {
+        let costs: Vec<_> = SPEEDS
+            .iter()
+            .map(|&speed| ans.cost(row.dst, speed))
+            .collect();
+        let turn_min = costs.iter().flatten().map(|&(turn, _)| turn).min();
+
+        let cells = costs.iter().flat_map(|&cost| match cost {
+            Some((turn, energy)) => {
+                let fastest = Some(turn) == turn_min;
+                [td![C![fastest.then_some("fastest")], turn], td![energy]]
+            }
+            None => [td!["-"], td!["-"]],
+        });
+
+        let name = model.planet_name(row.dst);
+        tr![
+            C![row.cost.is_none().then_some("unreachable")],
+            td![format!("{}:{name}", row.dst)],
+            td![row.dist],
+            cells,
+        ]
+    });
+
+    let header_speeds = SPEEDS.iter().map(|&speed| {
+        th![
+            attrs! {
+                At::ColSpan => 2,
+            },
+            format!("{} {speed}", t.speed),
+        ]
+    });
+    let header_columns = SPEEDS.iter().flat_map(|&speed| {
+        if speed == model.speed {
+            [
+                view_sort_header(model, SortKey::Turns, 1),
+                view_sort_header(model, SortKey::Energy, 1),
+            ]
+        } else {
+            [th![t.turns], th![t.consumption]]
+        }
+    });
+
+    table![
+        thead![
+            tr![
+                view_sort_header(model, SortKey::Id, 2),
+                view_sort_header(model, SortKey::Distance, 2),
+                header_speeds,
+            ],
+            tr![header_columns],
+        ],
+        tbody![rows],
+    ]
+}
@@ -0,0 +1,307 @@
+use crate::locale::Language;
+
+/// UI の文言。言語ごとに 1 つずつ定義する。
+///
+/// ラベルの後に続く ": " などの区切りは含めない。
+#[derive(Debug)]
+pub(super) struct Text {
+    pub(super) language: &'static str,
+    pub(super) reset_settings: &'static str,
+
+    pub(super) page_query: &'static str,
+    pub(super) page_matrix: &'static str,
+    pub(super) page_projection: &'static str,
+    pub(super) page_map: &'static str,
+    pub(super) page_graph: &'static str,
+    pub(super) page_sqrt: &'static str,
+
+    pub(super) speed: &'static str,
+    pub(super) compare_speeds: &'static str,
+    pub(super) source: &'static str,
+    pub(super) source_position: &'static str,
+    pub(super) energy: &'static str,
+    pub(super) position: &'static str,
+    pub(super) search_planet: &'static str,
+
+    pub(super) destination: &'static str,
+    pub(super) distance: &'static str,
+    pub(super) turns: &'static str,
+    pub(super) consumption: &'static str,
+    pub(super) unreachable: &'static str,
+
+    pub(super) sort: &'static str,
+    pub(super) ascending: &'static str,
+    pub(super) descending: &'static str,
+    pub(super) name: &'static str,
+    pub(super) hide_unreachable: &'static str,
+
+    pub(super) matrix_corner: &'static str,
+
+    pub(super) volume: &'static str,
+    pub(super) volume_large: &'static str,
+    pub(super) volume_medium: &'static str,
+    pub(super) volume_small: &'static str,
+    pub(super) volume_tiny: &'static str,
+
+    pub(super) detail_hint: &'static str,
+    pub(super) planet: &'static str,
+    pub(super) coordinates: &'static str,
+    pub(super) neighbors: &'static str,
+    pub(super) distance_from_source: &'static str,
+    pub(super) energy_to_order: &'static str,
+
+    pub(super) playback: &'static str,
+    pub(super) playback_requires_planet: &'static str,
+    pub(super) play: &'static str,
+    pub(super) pause: &'static str,
+    pub(super) step: &'static str,
+    pub(super) turns_unit: &'static str,
+
+    pub(super) projection_xy: &'static str,
+    pub(super) projection_xz: &'static str,
+    pub(super) projection_yz: &'static str,
+    pub(super) projection_isometric: &'static str,
+
+    pub(super) zoom_fit: &'static str,
+    pub(super) show_labels: &'static str,
+    pub(super) legend_source: &'static str,
+    pub(super) legend_reachable: &'static str,
+    pub(super) legend_unreachable: &'static str,
+
+    pub(super) map_current: &'static str,
+    pub(super) map_download: &'static str,
+    pub(super) map_reset: &'static str,
+    pub(super) map_load: &'static str,
+    pub(super) map_load_hint: &'static str,
+    pub(super) map_violations: &'static str,
+    pub(super) map_no_violations: &'static str,
+    pub(super) map_parse_error: &'static str,
+    pub(super) map_invalid: &'static str,
+    pub(super) severity_error: &'static str,
+    pub(super) severity_warning: &'static str,
+
+    pub(super) edit_map: &'static str,
+    pub(super) edit_done: &'static str,
+    pub(super) edit_hint: &'static str,
+    pub(super) add_neighbor: &'static str,
+
+    pub(super) graph_diameter: &'static str,
+    pub(super) graph_articulation_points: &'static str,
+    pub(super) graph_bridges: &'static str,
+    pub(super) graph_betweenness: &'static str,
+    pub(super) graph_hops: &'static str,
+    pub(super) graph_path: &'static str,
+    pub(super) graph_none: &'static str,
+    pub(super) graph_requires_planet: &'static str,
+
+    pub(super) sqrt_exact: &'static str,
+    pub(super) sqrt_rounded: &'static str,
+    pub(super) sqrt_note: &'static str,
+    pub(super) sqrt_deltas: &'static str,
+    pub(super) sqrt_travel_changes: &'static str,
+    pub(super) sqrt_order_changes: &'static str,
+}
+
+const JA: Text = Text {
+    language: "言語",
+    reset_settings: "設定をリセット",
+
+    page_query: "検索",
+    page_matrix: "全惑星間",
+    page_projection: "座標投影",
+    page_map: "マップ定義",
+    page_graph: "航路解析",
+    page_sqrt: "平方根の比較",
+
+    speed: "移動力",
+    compare_speeds: "全移動力を比較",
+    source: "始点",
+    source_position: "座標指定",
+    energy: "エネルギー",
+    position: "座標",
+    search_planet: "惑星名で検索",
+
+    destination: "目的地",
+    distance: "距離",
+    turns: "日数",
+    consumption: "消費",
+    unreachable: "到達不能",
+
+    sort: "並べ替え",
+    ascending: "昇順",
+    descending: "降順",
+    name: "名前",
+    hide_unreachable: "到達不能を隠す",
+
+    matrix_corner: "始点＼目的地",
+
+    volume: "規模",
+    volume_large: "大",
+    volume_medium: "中",
+    volume_small: "小",
+    volume_tiny: "極小",
+
+    detail_hint: "マップ上の惑星にカーソルを合わせると詳細を表示する。",
+    planet: "惑星",
+    coordinates: "座標",
+    neighbors: "隣接",
+    distance_from_source: "始点からの距離",
+    energy_to_order: "移動命令に必要なエネルギー",
+
+    playback: "移動の再生",
+    playback_requires_planet: "移動の再生は始点が惑星の場合のみ可能",
+    play: "再生",
+    pause: "一時停止",
+    step: "コマ送り",
+    turns_unit: "ターン",
+
+    projection_xy: "XY 平面",
+    projection_xz: "XZ 平面",
+    projection_yz: "YZ 平面",
+    projection_isometric: "等角投影",
+
+    zoom_fit: "全体表示",
+    show_labels: "惑星名を表示",
+    legend_source: "始点",
+    legend_reachable: "移動命令を出せる",
+    legend_unreachable: "移動命令を出せない",
+
+    map_current: "現在のマップ (惑星数)",
+    map_download: "JSON でダウンロード",
+    map_reset: "組み込みのマップに戻す",
+    map_load: "読み込む",
+    map_load_hint: "マップ定義の JSON を貼り付けて読み込む。形式はダウンロードしたものと同じ。",
+    map_violations: "現在のマップの検査結果",
+    map_no_violations: "問題なし",
+    map_parse_error: "JSON として読み込めない",
+    map_invalid: "マップ定義が不正",
+    severity_error: "エラー",
+    severity_warning: "警告",
+
+    edit_map: "マップを編集",
+    edit_done: "編集を終える",
+    edit_hint: "惑星をクリックで選択、ドラッグでマップ上の位置を変更できる。編集したマップはマップ定義のページからダウンロードできる。",
+    add_neighbor: "隣接惑星を追加",
+
+    graph_diameter: "直径 (ホップ数)",
+    graph_articulation_points: "関節点 (失うとマップが分断される惑星)",
+    graph_bridges: "橋 (失うとマップが分断される航路)",
+    graph_betweenness: "媒介中心性",
+    graph_hops: "ホップ数",
+    graph_path: "経路",
+    graph_none: "なし",
+    graph_requires_planet: "始点に惑星を選ぶと、各惑星へのホップ数最小の経路を表示する。",
+
+    sqrt_exact: "厳密 (切り捨て)",
+    sqrt_rounded: "四捨五入",
+    sqrt_note: "距離の平方根を切り捨てた場合と四捨五入した場合の比較。どちらもゲームの平方根ルーチンを再現したものではない。他のページの計算は常に切り捨てを使う。",
+    sqrt_deltas: "距離が異なる座標の差 (各方向 128 以下)",
+    sqrt_travel_changes: "所要ターン数・エネルギーが変わる惑星の組 (現在の移動力)",
+    sqrt_order_changes: "移動命令に必要なエネルギーが変わる惑星の組",
+};
+
+const EN: Text = Text {
+    language: "Language",
+    reset_settings: "Reset settings",
+
+    page_query: "Search",
+    page_matrix: "All pairs",
+    page_projection: "Projections",
+    page_map: "Map data",
+    page_graph: "Corridor analysis",
+    page_sqrt: "Square root comparison",
+
+    speed: "Speed",
+    compare_speeds: "Compare all speeds",
+    source: "Source",
+    source_position: "Coordinates",
+    energy: "Energy",
+    position: "Position",
+    search_planet: "Find planet by name",
+
+    destination: "Destination",
+    distance: "Distance",
+    turns: "Turns",
+    consumption: "Energy used",
+    unreachable: "Unreachable",
+
+    sort: "Sort by",
+    ascending: "Ascending",
+    descending: "Descending",
+    name: "Name",
+    hide_unreachable: "Hide unreachable",
+
+    matrix_corner: "Source \\ Destination",
+
+    volume: "Size",
+    volume_large: "Large",
+    volume_medium: "Medium",
+    volume_small: "Small",
+    volume_tiny: "Tiny",
+
+    detail_hint: "Hover over a planet on the map to show its details.",
+    planet: "Planet",
+    coordinates: "Coordinates",
+    neighbors: "Neighbors",
+    distance_from_source: "Distance from source",
+    energy_to_order: "Energy required to order",
+
+    playback: "Movement playback",
+    playback_requires_planet: "Playback is available only when the source is a planet",
+    play: "Play",
+    pause: "Pause",
+    step: "Step",
+    turns_unit: "turns",
+
+    projection_xy: "XY plane",
+    projection_xz: "XZ plane",
+    projection_yz: "YZ plane",
+    projection_isometric: "Isometric",
+
+    zoom_fit: "Fit",
+    show_labels: "Show planet names",
+    legend_source: "Source",
+    legend_reachable: "Can be ordered",
+    legend_unreachable: "Cannot be ordered",
+
+    map_current: "Current map (planets)",
+    map_download: "Download as JSON",
+    map_reset: "Restore built-in map",
+    map_load: "Load",
+    map_load_hint:
+        "Paste a map definition in JSON and load it. The format is the same as the download.",
+    map_violations: "Validation of the current map",
+    map_no_violations: "No problems found",
+    map_parse_error: "Cannot be read as JSON",
+    map_invalid: "The map definition is invalid",
+    severity_error: "Error",
+    severity_warning: "Warning",
+
+    edit_map: "Edit map",
+    edit_done: "Finish editing",
+    edit_hint: "Click a planet to select it, drag it to move it on the map. The edited map can be downloaded from the map data page.",
+    add_neighbor: "Add neighbor",
+
+    graph_diameter: "Diameter (hops)",
+    graph_articulation_points: "Articulation points (planets whose loss splits the map)",
+    graph_bridges: "Bridges (corridors whose loss splits the map)",
+    graph_betweenness: "Betweenness centrality",
+    graph_hops: "Hops",
+    graph_path: "Path",
+    graph_none: "None",
+    graph_requires_planet: "Select a source planet to show the paths with the fewest hops.",
+
+    sqrt_exact: "Exact (floor)",
+    sqrt_rounded: "Rounded to nearest",
+    sqrt_note: "Compares flooring and rounding the square root in distances. Neither reproduces the game's own square root routine. The other pages always use flooring.",
+    sqrt_deltas: "Coordinate deltas with different distances (up to 128 per axis)",
+    sqrt_travel_changes: "Planet pairs whose turns or energy change (current speed)",
+    sqrt_order_changes: "Planet pairs whose energy to order changes",
+};
+
+pub(super) fn text(lang: Language) -> &'static Text {
+    match lang {
+        Language::Japanese => &JA,
+        Language::English => &EN,
+    }
+}
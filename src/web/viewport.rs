@@ -0,0 +1,244 @@
+use seed::{prelude::*, *};
+
+use super::{planet_image_id, Model, Msg, PlanetColor, PlanetVolume, MAP_HEIGHT, MAP_WIDTH};
+
+const ZOOM_MIN: f64 = 1.0;
+const ZOOM_MAX: f64 = 8.0;
+
+/// ボタンおよびマウスホイール 1 回あたりの拡大率。
+const ZOOM_STEP: f64 = 1.25;
+
+/// マップの表示範囲 (拡大率と中心) およびラベル表示の状態。
+#[derive(Debug)]
+pub(super) struct Viewport {
+    zoom: f64,
+    center: (f64, f64),
+    /// ドラッグ中なら直前のポインタ位置 (クライアント座標)。
+    drag: Option<(f64, f64)>,
+    /// 2 本指でピンチ中なら直前の指の間隔 (ピクセル)。
+    pinch: Option<f64>,
+    show_labels: bool,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            zoom: ZOOM_MIN,
+            center: (f64::from(MAP_WIDTH) / 2.0, f64::from(MAP_HEIGHT) / 2.0),
+            drag: None,
+            pinch: None,
+            show_labels: false,
+        }
+    }
+}
+
+impl Viewport {
+    pub(super) fn show_labels(&self) -> bool {
+        self.show_labels
+    }
+
+    pub(super) fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// SVG の `viewBox` 属性値を返す。
+    pub(super) fn view_box(&self) -> String {
+        let w = f64::from(MAP_WIDTH) / self.zoom;
+        let h = f64::from(MAP_HEIGHT) / self.zoom;
+        let x = self.center.0 - w / 2.0;
+        let y = self.center.1 - h / 2.0;
+
+        format!("{x} {y} {w} {h}")
+    }
+
+    fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).clamp(ZOOM_MIN, ZOOM_MAX);
+        self.clamp_center();
+    }
+
+    /// ポインタの移動量 (ピクセル) だけマップをずらす。
+    fn pan_by(&mut self, dx: f64, dy: f64) {
+        self.center.0 -= dx / self.zoom;
+        self.center.1 -= dy / self.zoom;
+        self.clamp_center();
+    }
+
+    /// 表示範囲がマップの外にはみ出さないよう中心を制限する。
+    fn clamp_center(&mut self) {
+        let half_w = f64::from(MAP_WIDTH) / self.zoom / 2.0;
+        let half_h = f64::from(MAP_HEIGHT) / self.zoom / 2.0;
+        self.center.0 = self.center.0.clamp(half_w, f64::from(MAP_WIDTH) - half_w);
+        self.center.1 = self.center.1.clamp(half_h, f64::from(MAP_HEIGHT) - half_h);
+    }
+}
+
+#[derive(Debug)]
+pub(super) enum ViewportMsg {
+    ZoomIn,
+    ZoomOut,
+    Reset,
+    PointerDown(f64, f64),
+    PointerMove(f64, f64),
+    PointerUp,
+    /// タッチ中の指の位置 (クライアント座標) の一覧。
+    Touch(Vec<(f64, f64)>),
+    ToggleLabels,
+}
+
+pub(super) fn update(msg: ViewportMsg, viewport: &mut Viewport) {
+    match msg {
+        ViewportMsg::ZoomIn => viewport.zoom_by(ZOOM_STEP),
+        ViewportMsg::ZoomOut => viewport.zoom_by(ZOOM_STEP.recip()),
+        ViewportMsg::Reset => {
+            *viewport = Viewport {
+                show_labels: viewport.show_labels,
+                ..Default::default()
+            }
+        }
+        ViewportMsg::PointerDown(x, y) => viewport.drag = Some((x, y)),
+        ViewportMsg::PointerMove(x, y) => {
+            if let Some((x_prev, y_prev)) = viewport.drag {
+                viewport.pan_by(x - x_prev, y - y_prev);
+                viewport.drag = Some((x, y));
+            }
+        }
+        ViewportMsg::PointerUp => viewport.drag = None,
+        ViewportMsg::Touch(points) => match points[..] {
+            // 1 本指ならドラッグ、2 本指ならピンチとして扱う。
+            [(x, y)] => {
+                viewport.pinch = None;
+                update(ViewportMsg::PointerMove(x, y), viewport);
+                viewport.drag = Some((x, y));
+            }
+            [(x1, y1), (x2, y2)] => {
+                viewport.drag = None;
+                let dist = (x1 - x2).hypot(y1 - y2);
+                if let Some(dist_prev) = viewport.pinch.filter(|&d| d > 0.0) {
+                    viewport.zoom_by(dist / dist_prev);
+                }
+                viewport.pinch = Some(dist);
+            }
+            _ => {
+                viewport.drag = None;
+                viewport.pinch = None;
+            }
+        },
+        ViewportMsg::ToggleLabels => viewport.show_labels = !viewport.show_labels,
+    }
+}
+
+/// マップの SVG 要素に付けるズーム/パン用のイベントハンドラ。
+pub(super) fn event_handlers() -> Vec<EventHandler<Msg>> {
+    fn touches(ev: &web_sys::TouchEvent) -> Vec<(f64, f64)> {
+        let list = ev.touches();
+        (0..list.length())
+            .filter_map(|i| list.get(i))
+            .map(|touch| (f64::from(touch.client_x()), f64::from(touch.client_y())))
+            .collect()
+    }
+
+    vec![
+        wheel_ev(Ev::Wheel, |ev| {
+            ev.prevent_default();
+            let msg = if ev.delta_y() < 0.0 {
+                ViewportMsg::ZoomIn
+            } else {
+                ViewportMsg::ZoomOut
+            };
+            Msg::Viewport(msg)
+        }),
+        mouse_ev(Ev::MouseDown, |ev| {
+            ev.prevent_default();
+            Msg::Viewport(ViewportMsg::PointerDown(
+                f64::from(ev.client_x()),
+                f64::from(ev.client_y()),
+            ))
+        }),
+        mouse_ev(Ev::MouseMove, |ev| {
+            Msg::Viewport(ViewportMsg::PointerMove(
+                f64::from(ev.client_x()),
+                f64::from(ev.client_y()),
+            ))
+        }),
+        mouse_ev(Ev::MouseUp, |_| Msg::Viewport(ViewportMsg::PointerUp)),
+        mouse_ev(Ev::MouseLeave, |_| Msg::Viewport(ViewportMsg::PointerUp)),
+        touch_ev(Ev::TouchStart, |ev| {
+            let points = touches(&ev);
+            // タッチ開始時は移動量を計算せず、位置のみ記録する。
+            Msg::Viewport(match points[..] {
+                [(x, y)] => ViewportMsg::PointerDown(x, y),
+                _ => ViewportMsg::Touch(points),
+            })
+        }),
+        touch_ev(Ev::TouchMove, |ev| {
+            ev.prevent_default();
+            Msg::Viewport(ViewportMsg::Touch(touches(&ev)))
+        }),
+        touch_ev(Ev::TouchEnd, |ev| {
+            Msg::Viewport(ViewportMsg::Touch(touches(&ev)))
+        }),
+    ]
+}
+
+/// ズーム操作のボタンとラベル表示の切り替え。
+pub(super) fn view_controls(model: &Model) -> Node<Msg> {
+    const ID_CHECKBOX: &str = "checkbox-map-labels";
+
+    let t = model.text();
+    let viewport = &model.viewport;
+
+    p![
+        button!["＋", ev(Ev::Click, |_| Msg::Viewport(ViewportMsg::ZoomIn))],
+        button!["－", ev(Ev::Click, |_| Msg::Viewport(ViewportMsg::ZoomOut))],
+        button![
+            t.zoom_fit,
+            ev(Ev::Click, |_| Msg::Viewport(ViewportMsg::Reset))
+        ],
+        " ",
+        input![
+            id!(ID_CHECKBOX),
+            attrs! {
+                At::Type => "checkbox",
+                At::Checked => viewport.show_labels.as_at_value(),
+            },
+            ev(Ev::Change, |_| Msg::Viewport(ViewportMsg::ToggleLabels)),
+        ],
+        label![
+            attrs! {
+                At::For => ID_CHECKBOX,
+            },
+            t.show_labels,
+        ],
+    ]
+}
+
+/// 惑星画像の色と大きさの凡例。
+pub(super) fn view_legend(model: &Model) -> Node<Msg> {
+    let t = model.text();
+
+    let sprite = |volume, color| {
+        img![attrs! {
+            At::Src => format!("asset/{}.png", planet_image_id(volume, color)),
+            At::Width => 32,
+            At::Height => 32,
+        }]
+    };
+
+    let colors = PlanetColor::iter().map(|color| {
+        let desc = match color {
+            PlanetColor::Source => t.legend_source,
+            PlanetColor::Reachable => t.legend_reachable,
+            PlanetColor::Unreachable => t.legend_unreachable,
+        };
+        li![sprite(PlanetVolume::Large, color), desc]
+    });
+
+    let volumes = PlanetVolume::iter().map(|volume| {
+        li![
+            sprite(volume, PlanetColor::Unreachable),
+            format!("{}: {}", t.volume, volume.label(t))
+        ]
+    });
+
+    div![C!["legend"], ul![colors], ul![volumes]]
+}
@@ -0,0 +1,29 @@
+//! 結合テストで共有するマップの作成。
+
+use logh_travel::starmap::StarMap;
+
+/// 名前, 座標, 隣接惑星, マップ上の位置。
+pub type PlanetDef<'a> = (&'a str, [u32; 3], &'a [usize], [u32; 2]);
+
+/// 惑星の定義の並びからマップを作る。
+///
+/// `StarMap::from_json` と違って検証しないので、検証でエラーとなるマップも作れる。
+/// 英語名は日本語名と同じ、体積は全て `tiny` とする。
+pub fn map(planets: &[PlanetDef]) -> StarMap {
+    let planets: Vec<_> = planets
+        .iter()
+        .map(|(name, position, neighbors, map_position)| {
+            serde_json::json!({
+                "name": name,
+                "name_en": name,
+                "position": position,
+                "neighbors": neighbors,
+                "volume": "tiny",
+                "map_position": map_position,
+            })
+        })
+        .collect();
+    let json = serde_json::json!({ "name": "test", "planets": planets });
+
+    serde_json::from_value(json).unwrap()
+}
@@ -0,0 +1,48 @@
+use logh_travel::export::{Cell, Format, Table};
+
+fn table() -> Table {
+    let mut table = Table::new(&["id", "name", "reachable", "turns"]);
+    table.push_row(vec![0.into(), "a,b".into(), true.into(), Cell::Empty]);
+    table.push_row(vec![1.into(), "x\r\ny".into(), false.into(), 3.into()]);
+    table.push_row(vec![2.into(), "\"q\"|\\".into(), false.into(), 4.into()]);
+
+    table
+}
+
+#[test]
+fn csv_quotes_special_characters() {
+    assert_eq!(
+        table().to_string(Format::Csv),
+        "id,name,reachable,turns\n0,\"a,b\",true,\n1,\"x\r\ny\",false,3\n2,\"\"\"q\"\"|\\\",false,4\n"
+    );
+
+    let mut lone_cr = Table::new(&["name"]);
+    lone_cr.push_row(vec!["x\ry".into()]);
+    assert_eq!(lone_cr.to_string(Format::Csv), "name\n\"x\ry\"\n");
+}
+
+#[test]
+fn json_keeps_column_order() {
+    let json = table().to_string(Format::Json);
+
+    assert!(json.starts_with(r#"[{"id":0,"name":"a,b","reachable":true,"turns":null},"#));
+    assert!(json.ends_with("}]\n"));
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!([
+            {"id": 0, "name": "a,b", "reachable": true, "turns": null},
+            {"id": 1, "name": "x\r\ny", "reachable": false, "turns": 3},
+            {"id": 2, "name": "\"q\"|\\", "reachable": false, "turns": 4},
+        ])
+    );
+}
+
+#[test]
+fn markdown_escapes_pipes() {
+    let md = table().to_string(Format::Markdown);
+
+    assert!(md.starts_with("| id | name | reachable | turns |\n| --- | --- | --- | --- |\n"));
+    assert!(md.contains("| 2 | \"q\"\\|\\ | false | 4 |\n"));
+}
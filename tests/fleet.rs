@@ -0,0 +1,141 @@
+//! 艦隊の 1 ターンの移動 (`move_fleet`、`FcRules::step` として公開) の性質のテスト。
+//!
+//! 座標は入力欄と同じ 0..=128 の範囲とする。ゲームの移動力は 10 以上だが、性質が成り立つ範囲を
+//! 確かめるためにより小さい移動力も含める。性質を満たさない入力は末尾に回帰テストとして残す。
+
+use proptest::prelude::*;
+
+use logh_travel::geometry::Vec3;
+use logh_travel::travel::{FcRules, MovementRules};
+
+fn position() -> impl Strategy<Value = Vec3> {
+    (0..=128u32, 0..=128u32, 0..=128u32).prop_map(|(x, y, z)| Vec3::new(x, y, z))
+}
+
+/// 2 点間のユークリッド距離の 2 乗。
+fn distance_squared(p1: Vec3, p2: Vec3) -> u32 {
+    p1.x.abs_diff(p2.x).pow(2) + p1.y.abs_diff(p2.y).pow(2) + p1.z.abs_diff(p2.z).pow(2)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(4096))]
+
+    /// どの方向にも目的地を通り越さない。
+    #[test]
+    fn never_overshoots(src in position(), target in position(), speed in 1..=30u32) {
+        let p = FcRules.step(src, target, speed);
+
+        for (s, t, p) in [(src.x, target.x, p.x), (src.y, target.y, p.y), (src.z, target.z, p.z)] {
+            prop_assert!(s.min(t) <= p && p <= s.max(t));
+        }
+    }
+
+    /// 1 ターンの移動距離は、平方根を切り捨てた距離 (`Vec3::distance`) で移動力以下。
+    ///
+    /// ユークリッド距離では移動力以下にならない。反例は `regression_euclidean_step_exceeds_speed`。
+    #[test]
+    fn moves_at_most_speed(src in position(), target in position(), speed in 1..=30u32) {
+        let p = FcRules.step(src, target, speed);
+
+        prop_assert!(src.distance(p) <= speed);
+    }
+
+    /// 目的地までのユークリッド距離は真に減る。移動力 1 では減らない場合がある。
+    #[test]
+    fn strictly_approaches(src in position(), target in position(), speed in 2..=30u32) {
+        prop_assume!(src != target);
+        let p = FcRules.step(src, target, speed);
+
+        prop_assert!(distance_squared(p, target) < distance_squared(src, target));
+    }
+
+    /// 切り捨てた距離で見ても目的地に真に近づく。移動力 2 以下では近づかない場合がある。
+    #[test]
+    fn strictly_approaches_in_floored_distance(
+        src in position(),
+        target in position(),
+        speed in 3..=30u32,
+    ) {
+        prop_assume!(src != target);
+        let p = FcRules.step(src, target, speed);
+
+        prop_assert!(p.distance(target) < src.distance(target));
+    }
+
+    /// 実所要ターン数は ceil(距離 / 移動力) 以上。
+    #[test]
+    fn turns_at_least_distance_over_speed(
+        src in position(),
+        target in position(),
+        speed in 2..=30u32,
+    ) {
+        let (turns, _) = FcRules.actual_turns_and_energy(src, target, speed);
+
+        prop_assert!(turns >= src.distance(target).div_ceil(speed));
+    }
+}
+
+/// 各方向の移動量を切り捨てて求めるため、ユークリッド距離では移動力を超えて移動しうる。
+/// ここでは (1, 2, 17) 移動し、その長さは √294 > 17 だが、切り捨てた距離は 17。
+#[test]
+fn regression_euclidean_step_exceeds_speed() {
+    let src = Vec3::new(95, 25, 80);
+    let target = Vec3::new(99, 15, 12);
+
+    let p = FcRules.step(src, target, 17);
+
+    assert_eq!(p, Vec3::new(96, 23, 63));
+    assert_eq!(distance_squared(src, p), 294);
+    assert_eq!(src.distance(p), 17);
+}
+
+/// 最終ターンは切り捨てた距離が移動力以下なら目的地に着くので、これもユークリッド距離では移動力を超えうる。
+#[test]
+fn regression_last_step_exceeds_speed() {
+    let src = Vec3::new(0, 0, 0);
+    let target = Vec3::new(10, 10, 10);
+
+    assert_eq!(src.distance(target), 17);
+    assert_eq!(FcRules.step(src, target, 17), target);
+    assert!(distance_squared(src, target) > 17 * 17);
+}
+
+/// 移動力 1 では、どの方向の移動量も切り捨てで 0 になり、移動しないことがある。
+#[test]
+fn regression_speed_one_stalls() {
+    let src = Vec3::new(0, 0, 0);
+
+    assert_eq!(FcRules.step(src, Vec3::new(0, 3, 3), 1), src);
+    assert_eq!(FcRules.step(src, Vec3::new(0, 16, 6), 1), src);
+}
+
+/// 移動しない場合、到着までの計算は終わらずに続けるのではなくパニックする。
+#[test]
+#[should_panic(expected = "移動しない")]
+fn speed_one_stall_panics() {
+    FcRules.actual_turns_and_energy(Vec3::new(0, 0, 0), Vec3::new(0, 3, 3), 1);
+}
+
+#[test]
+#[should_panic(expected = "移動しない")]
+fn speed_one_stall_panics_in_fleet_path() {
+    FcRules.fleet_path(Vec3::new(0, 0, 0), Vec3::new(0, 16, 6), 1);
+}
+
+#[test]
+#[should_panic(expected = "移動力が 0")]
+fn speed_zero_panics() {
+    FcRules.step(Vec3::new(0, 0, 0), Vec3::new(1, 2, 3), 0);
+}
+
+/// 移動力 2 では、移動しても切り捨てた距離が変わらないことがある。
+#[test]
+fn regression_speed_two_keeps_floored_distance() {
+    let src = Vec3::new(0, 0, 0);
+    let target = Vec3::new(0, 5, 13);
+
+    let p = FcRules.step(src, target, 2);
+
+    assert_ne!(p, src);
+    assert_eq!(p.distance(target), src.distance(target));
+}
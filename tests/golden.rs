@@ -0,0 +1,187 @@
+//! 既知の結果を再現できるかの回帰テスト。
+//!
+//! - `tests/fixtures/golden_derived.json`: FC 版のルールから手計算で求めた少数のケース。各ケースに計算の過程を記す。
+//! - `tests/fixtures/golden.json`: 実装から生成したスナップショット。ゲーム内で確かめたものではなく、
+//!   実装の意図しない変化を検出するためのもの。ルールを意図的に変えた場合は
+//!   `cargo test --test golden -- --ignored regenerate_snapshot` で作り直す。
+
+use serde::Deserialize;
+
+use logh_travel::geometry::Vec3;
+use logh_travel::starmap::StarMap;
+use logh_travel::travel;
+
+const SNAPSHOT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden.json");
+
+#[derive(Debug, Deserialize)]
+struct Golden {
+    travel: Vec<TravelCase>,
+    reachable: Vec<ReachableCase>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TravelCase {
+    /// 手計算したケースの出典と計算の過程。スナップショットにはない。
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    derivation: Option<String>,
+    src: Vec3,
+    target: Vec3,
+    speed: u32,
+    turns: u32,
+    energy: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReachableCase {
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    derivation: Option<String>,
+    src: usize,
+    energy: u32,
+    reachable: Vec<usize>,
+}
+
+fn snapshot() -> Golden {
+    serde_json::from_str(include_str!("fixtures/golden.json")).unwrap()
+}
+
+fn derived() -> Golden {
+    serde_json::from_str(include_str!("fixtures/golden_derived.json")).unwrap()
+}
+
+fn travel_failures(golden: &Golden) -> Vec<String> {
+    assert!(!golden.travel.is_empty());
+
+    // 1 件ずつ止めずに、一致しないケースを全て報告する。
+    golden
+        .travel
+        .iter()
+        .filter_map(|case| {
+            let actual = travel::actual_turns_and_energy(case.src, case.target, case.speed);
+            (actual != (case.turns, case.energy)).then(|| format!("{case:?}: actual {actual:?}"))
+        })
+        .collect()
+}
+
+fn reachable_failures(golden: &Golden) -> Vec<String> {
+    assert!(!golden.reachable.is_empty());
+    let map = StarMap::builtin();
+
+    golden
+        .reachable
+        .iter()
+        .filter_map(|case| {
+            let actual: Vec<_> = travel::reachable_planets(map, case.src, case.energy)
+                .iter()
+                .collect();
+            (actual != case.reachable).then(|| format!("{case:?}: actual {actual:?}"))
+        })
+        .collect()
+}
+
+#[test]
+fn derived_cases_are_documented() {
+    let golden = derived();
+    let sources = golden
+        .travel
+        .iter()
+        .map(|case| (&case.source, &case.derivation))
+        .chain(
+            golden
+                .reachable
+                .iter()
+                .map(|case| (&case.source, &case.derivation)),
+        );
+
+    for (source, derivation) in sources {
+        assert_eq!(source.as_deref(), Some("hand-derived"));
+        assert!(derivation.as_ref().is_some_and(|d| !d.is_empty()));
+    }
+}
+
+#[test]
+fn derived_actual_turns_and_energy() {
+    let failures = travel_failures(&derived());
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn derived_reachable_planets() {
+    let failures = reachable_failures(&derived());
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn snapshot_actual_turns_and_energy() {
+    let failures = travel_failures(&snapshot());
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn snapshot_reachable_planets() {
+    let failures = reachable_failures(&snapshot());
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// スナップショットを現在の実装から作り直す。
+///
+/// 各惑星から ID が 1, 7, 13 離れた惑星への移動と、境界付近の座標の組を、ゲームの移動力ごとに求める。
+/// 到達可能な惑星は、各惑星からエネルギー 0, 20, ..., 100 で求める。
+#[test]
+#[ignore = "スナップショットを上書きする"]
+fn regenerate_snapshot() {
+    const SPEEDS: [u32; 5] = [30, 20, 16, 12, 10];
+    const NOTE: &str = "組み込みのマップと FC 版のルール (FcRules) の実装から生成した回帰テスト用の値。ゲーム内で確かめた値ではない。tests/golden.rs の regenerate_snapshot で生成する。";
+
+    let map = StarMap::builtin();
+    let n = map.planet_count();
+    let position = |id: usize| map.planet(id).position();
+    let xyz = |p: Vec3| format!("[{}, {}, {}]", p.x, p.y, p.z);
+
+    let mut pairs = vec![];
+    for src in 0..n {
+        for k in [1, 7, 13] {
+            pairs.push((position(src), position((src + k) % n)));
+        }
+    }
+    pairs.extend([
+        (Vec3::new(0, 0, 0), Vec3::new(0, 0, 0)),
+        (Vec3::new(0, 0, 0), Vec3::new(128, 128, 128)),
+        (Vec3::new(128, 0, 64), Vec3::new(0, 128, 1)),
+        (Vec3::new(5, 5, 5), Vec3::new(6, 6, 6)),
+        (Vec3::new(10, 0, 0), Vec3::new(0, 0, 0)),
+    ]);
+    let travel: Vec<_> = itertools::iproduct!(pairs, SPEEDS)
+        .map(|((src, target), speed)| {
+            let (turns, energy) = travel::actual_turns_and_energy(src, target, speed);
+            format!(
+                r#"    {{"src": {}, "target": {}, "speed": {speed}, "turns": {turns}, "energy": {energy}}}"#,
+                xyz(src),
+                xyz(target)
+            )
+        })
+        .collect();
+
+    let reachable: Vec<_> = itertools::iproduct!(0..n, [0, 20, 40, 60, 80, 100])
+        .map(|(src, energy)| {
+            let ids: Vec<_> = travel::reachable_planets(map, src, energy)
+                .iter()
+                .map(|id| id.to_string())
+                .collect();
+            format!(
+                r#"    {{"src": {src}, "energy": {energy}, "reachable": [{}]}}"#,
+                ids.join(", ")
+            )
+        })
+        .collect();
+
+    let json = format!(
+        "{{\n  \"note\": \"{NOTE}\",\n  \"travel\": [\n{}\n  ],\n  \"reachable\": [\n{}\n  ]\n}}\n",
+        travel.join(",\n"),
+        reachable.join(",\n")
+    );
+    std::fs::write(SNAPSHOT_PATH, json).unwrap();
+}
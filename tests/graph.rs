@@ -0,0 +1,184 @@
+mod common;
+
+use logh_travel::graph::{self, GraphFormat};
+use logh_travel::locale::Language;
+use logh_travel::starmap::StarMap;
+use logh_travel::travel::{self, FcRules};
+
+/// 隣接関係の並びから、惑星を x 軸上に 8 ずつ離して並べたマップを作る。名前は ID とする。
+fn map(neighbors: &[&[usize]]) -> StarMap {
+    let names: Vec<_> = (0..neighbors.len()).map(|id| id.to_string()).collect();
+    let planets: Vec<_> = (0u32..)
+        .zip(&names)
+        .zip(neighbors)
+        .map(|((id, name), &neighbors)| (name.as_str(), [8 * id, 0, 0], neighbors, [0, 0]))
+        .collect();
+
+    common::map(&planets)
+}
+
+/// 三角形 0-1-2 と三角形 3-4-5 を 2-3 で繋いだグラフ。
+fn two_triangles() -> StarMap {
+    map(&[&[1, 2], &[0, 2], &[0, 1, 3], &[2, 4, 5], &[3, 5], &[3, 4]])
+}
+
+#[test]
+fn articulation_points_and_bridges() {
+    let map = two_triangles();
+
+    assert_eq!(graph::articulation_points(&map), [2, 3]);
+    assert_eq!(graph::bridges(&map), [(2, 3)]);
+}
+
+#[test]
+fn cycle_has_no_chokepoints() {
+    let map = map(&[&[1, 3], &[0, 2], &[1, 3], &[2, 0]]);
+
+    assert!(graph::articulation_points(&map).is_empty());
+    assert!(graph::bridges(&map).is_empty());
+}
+
+#[test]
+fn path_graph() {
+    let map = map(&[&[1], &[0, 2], &[1, 3], &[2]]);
+
+    assert_eq!(graph::articulation_points(&map), [1, 2]);
+    assert_eq!(graph::bridges(&map), [(0, 1), (1, 2), (2, 3)]);
+    assert_eq!(
+        graph::hop_distances(&map, 0),
+        [Some(0), Some(1), Some(2), Some(3)]
+    );
+    assert_eq!(graph::diameter(&map), Some((3, (0, 3))));
+    assert_eq!(graph::betweenness_centrality(&map), [0.0, 2.0, 2.0, 0.0]);
+}
+
+#[test]
+fn betweenness_of_two_triangles() {
+    let map = two_triangles();
+
+    // 2 は {0, 1} と {3, 4, 5} の間の 6 組、3 は {0, 1, 2} と {4, 5} の間の 6 組の経路の上にある。
+    assert_eq!(
+        graph::betweenness_centrality(&map),
+        [0.0, 0.0, 6.0, 6.0, 0.0, 0.0]
+    );
+    assert_eq!(graph::diameter(&map), Some((3, (0, 4))));
+}
+
+#[test]
+fn hop_path_prefers_shorter_distance() {
+    // 0 から 3 へは 0-4-3 と 0-1-3 がともに 2 ホップで、距離は 40 と 24。
+    let map = map(&[&[4, 1], &[0, 3], &[], &[4, 1], &[0, 3]]);
+
+    assert_eq!(graph::hop_path(&map, 0, 3), Some(vec![0, 1, 3]));
+    assert_eq!(graph::path_distance(&map, &[0, 1, 3]), 24);
+    assert_eq!(graph::path_distance(&map, &[0, 4, 3]), 40);
+    assert_eq!(graph::hop_path(&map, 0, 0), Some(vec![0]));
+    assert_eq!(graph::hop_path(&map, 0, 2), None);
+    assert_eq!(
+        graph::hop_distances(&map, 2),
+        [None, None, Some(0), None, None]
+    );
+}
+
+#[test]
+fn builtin_map_is_analysable() {
+    let map = StarMap::builtin();
+    let n = map.planet_count();
+
+    let (diameter, (src, dst)) = graph::diameter(map).unwrap();
+    assert_eq!(
+        graph::hop_path(map, src, dst).unwrap().len() as u32,
+        diameter + 1
+    );
+    assert!(graph::betweenness_centrality(map)
+        .iter()
+        .all(|&c| (0.0..=((n - 1) * (n - 2) / 2) as f64).contains(&c)));
+
+    // 橋の端点は、次数 1 でなければ関節点。
+    let articulation_points = graph::articulation_points(map);
+    for (id1, id2) in graph::bridges(map) {
+        for id in [id1, id2] {
+            assert!(map.planet(id).neighbors().len() == 1 || articulation_points.contains(&id));
+        }
+    }
+}
+
+#[test]
+fn export_dot() {
+    let map = map(&[&[1], &[0, 2], &[1]]);
+    let dot = graph::export(&map, &FcRules, 30, GraphFormat::Dot, Language::Japanese);
+
+    assert!(dot.starts_with("graph \"test\" {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot.contains(
+        r#"  1 [label="1:1", name="1", name_en="1", x=8, y=0, z=0, volume="tiny", pos="0,0!"];"#
+    ));
+    assert!(dot.contains(r#"  0 -- 1 [distance=8, turns=1, label="8"];"#));
+    assert!(dot.contains(r#"  1 -- 2 [distance=8, turns=1, label="8"];"#));
+    assert_eq!(dot.matches(" -- ").count(), 2);
+}
+
+#[test]
+fn export_graphml() {
+    let map = map(&[&[1], &[0, 2], &[1]]);
+    let xml = graph::export(&map, &FcRules, 30, GraphFormat::GraphMl, Language::Japanese);
+
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    assert!(xml.contains(r#"<graph id="test" edgedefault="undirected">"#));
+    assert_eq!(xml.matches("<node ").count(), 3);
+    assert_eq!(xml.matches("<edge ").count(), 2);
+    assert!(xml.contains(r#"<edge source="n0" target="n1">"#));
+    assert!(xml.contains(r#"<data key="distance">8</data>"#));
+    assert!(xml.ends_with("</graphml>\n"));
+}
+
+#[test]
+fn export_turns_depend_on_speed() {
+    let map = StarMap::builtin();
+    let (id1, id2) = map.edges().next().unwrap();
+    let position = |id| map.planet(id).position();
+
+    for speed in [30, 10] {
+        let (turns, _) = travel::actual_turns_and_energy(position(id1), position(id2), speed);
+        let distance = map.distance(id1, id2);
+        let dot = graph::export(map, &FcRules, speed, GraphFormat::Dot, Language::Japanese);
+        assert!(dot.contains(&format!(
+            "  {id1} -- {id2} [distance={distance}, turns={turns}, "
+        )));
+    }
+}
+
+#[test]
+fn export_escapes_names() {
+    let json = r#"{"name": "a\"b<c>", "planets": [
+        {"name": "x&y", "name_en": "q\\\"", "position": [0, 0, 0], "neighbors": [],
+         "volume": "tiny", "map_position": [0, 0]}
+    ]}"#;
+    let map: StarMap = serde_json::from_str(json).unwrap();
+
+    let dot = graph::export(&map, &FcRules, 30, GraphFormat::Dot, Language::Japanese);
+    assert!(dot.starts_with(r#"graph "a\"b<c>" {"#));
+    assert!(dot.contains(r#"name_en="q\\\"""#));
+
+    let xml = graph::export(&map, &FcRules, 30, GraphFormat::GraphMl, Language::Japanese);
+    assert!(xml.contains(r#"<graph id="a&quot;b&lt;c&gt;""#));
+    assert!(xml.contains("<data key=\"name\">x&amp;y</data>"));
+}
+
+#[test]
+fn export_labels_follow_language() {
+    let map = StarMap::builtin();
+
+    for (lang, label) in [
+        (Language::Japanese, "31:ハイネセン"),
+        (Language::English, "31:Heinessen"),
+    ] {
+        let dot = graph::export(map, &FcRules, 30, GraphFormat::Dot, lang);
+        assert!(dot.contains(&format!(
+            r#"  31 [label="{label}", name="ハイネセン", name_en="Heinessen", "#
+        )));
+
+        let xml = graph::export(map, &FcRules, 30, GraphFormat::GraphMl, lang);
+        assert!(xml.contains(&format!(r#"<data key="label">{label}</data>"#)));
+    }
+}
@@ -0,0 +1,233 @@
+//! IPS パッチの生成と、マップの表を書き込むパッチの往復のテスト。
+//!
+//! ROM は `tests/fixtures/rom_layout.json` の配置を使う合成したもので、実際のカートリッジではない。
+
+use logh_travel::geometry::Vec3;
+use logh_travel::ips::{IpsError, IpsPatch, IpsRecord};
+use logh_travel::locale::Language;
+use logh_travel::rom::{self, INesRom, RomError, RomLayout, TableDiff};
+use logh_travel::starmap::StarMap;
+
+fn layout() -> RomLayout {
+    RomLayout::from_json(include_str!("fixtures/rom_layout.json")).unwrap()
+}
+
+/// 表が空の ROM イメージ。トレーナーを付けて PRG ROM がファイルの先頭から離れるようにする。
+fn blank_rom() -> Vec<u8> {
+    let mut bytes = b"NES\x1a".to_vec();
+    bytes.extend([2, 1, 0x04, 0]);
+    bytes.resize(16 + 512, 0);
+    bytes.resize(bytes.len() + 2 * 16 * 1024, 0xff);
+    bytes.resize(bytes.len() + 8 * 1024, 0x55);
+
+    bytes
+}
+
+/// `map` の表を書き込むパッチを `bytes` に適用し、表を抽出する。
+fn round_trip(bytes: &[u8], map: &StarMap) -> (Vec<u8>, Vec<rom::RomPlanet>) {
+    let patch = rom::write_tables(bytes, &layout(), map)
+        .map(|patched| IpsPatch::diff(bytes, &patched).unwrap())
+        .unwrap();
+    let patch = IpsPatch::parse(&patch.to_bytes()).unwrap();
+    let patched = patch.apply(bytes);
+
+    let mut layout = layout();
+    layout.names = None;
+    let planets = rom::extract(&INesRom::parse(&patched).unwrap(), &layout).unwrap();
+
+    (patched, planets)
+}
+
+#[test]
+fn patch_format() {
+    let original = [0, 1, 2, 3, 4, 5];
+    let modified = [0, 9, 9, 3, 4, 8, 7];
+
+    let patch = IpsPatch::diff(&original, &modified).unwrap();
+
+    assert_eq!(
+        patch.records(),
+        [
+            IpsRecord {
+                offset: 1,
+                data: vec![9, 9],
+            },
+            IpsRecord {
+                offset: 5,
+                data: vec![8, 7],
+            },
+        ]
+    );
+    assert_eq!(
+        patch.to_bytes(),
+        b"PATCH\0\0\x01\0\x02\x09\x09\0\0\x05\0\x02\x08\x07EOF"
+    );
+    assert_eq!(patch.apply(&original), modified);
+    assert_eq!(IpsPatch::parse(&patch.to_bytes()).unwrap(), patch);
+
+    let empty = IpsPatch::diff(&original, &original).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.to_bytes(), b"PATCHEOF");
+}
+
+#[test]
+fn long_runs_are_split() {
+    let original = vec![0; 0x20000];
+    let modified = vec![1; 0x20000];
+
+    let patch = IpsPatch::diff(&original, &modified).unwrap();
+
+    let sizes: Vec<_> = patch.records().iter().map(|r| r.data.len()).collect();
+    assert_eq!(sizes, [0xffff, 0xffff, 2]);
+    assert_eq!(patch.apply(&original), modified);
+}
+
+#[test]
+fn record_never_starts_at_eof_offset() {
+    const EOF_OFFSET: usize = 0x45_4f46;
+    let original = vec![0; EOF_OFFSET + 2];
+    let mut modified = original.clone();
+    modified[EOF_OFFSET] = 1;
+
+    let patch = IpsPatch::diff(&original, &modified).unwrap();
+
+    assert_eq!(patch.records()[0].offset, EOF_OFFSET - 1);
+    assert_eq!(patch.records()[0].data, [0, 1]);
+    let patch = IpsPatch::parse(&patch.to_bytes()).unwrap();
+    assert_eq!(patch.apply(&original), modified);
+}
+
+#[test]
+fn parse_rle_record() {
+    let patch = IpsPatch::parse(b"PATCH\0\0\x02\0\0\0\x03\x07\0\0\x06\0\x01\x08EOF").unwrap();
+
+    assert_eq!(patch.apply(&[0; 4]), [0, 0, 7, 7, 7, 0, 8]);
+}
+
+#[test]
+fn errors() {
+    assert!(matches!(IpsPatch::parse(b"PATCX"), Err(IpsError::NotIps)));
+    assert!(matches!(
+        IpsPatch::parse(b"PATCH\0\0\x01\0\x02\x09"),
+        Err(IpsError::Truncated)
+    ));
+    assert!(matches!(
+        IpsPatch::parse(b"PATCH"),
+        Err(IpsError::Truncated)
+    ));
+    assert!(matches!(
+        IpsPatch::diff(&[0, 1], &[0]),
+        Err(IpsError::Shrunk)
+    ));
+
+    let original = vec![0; 0x100_0001];
+    let mut modified = original.clone();
+    modified[0x100_0000] = 1;
+    assert!(matches!(
+        IpsPatch::diff(&original, &modified),
+        Err(IpsError::OffsetTooLarge(0x100_0000))
+    ));
+}
+
+#[test]
+fn round_trip_builtin_map() {
+    let bytes = blank_rom();
+    let map = StarMap::builtin();
+
+    let (patched, planets) = round_trip(&bytes, map);
+
+    assert_eq!(rom::diff(&planets, map, Language::English), []);
+    // PRG ROM の外 (ヘッダ、トレーナー、CHR ROM) は変わらない。
+    let prg_offset = 16 + 512;
+    let prg_end = prg_offset + 2 * 16 * 1024;
+    assert_eq!(patched.len(), bytes.len());
+    assert_eq!(patched[..prg_offset], bytes[..prg_offset]);
+    assert_eq!(patched[prg_end..], bytes[prg_end..]);
+}
+
+#[test]
+fn round_trip_edited_map() {
+    let builtin = StarMap::builtin();
+    let (bytes, _) = round_trip(&blank_rom(), builtin);
+
+    let mut map = builtin.clone();
+    map.set_position(3, Vec3::new(10, 20, 30));
+    let (id1, id2) = map.edges().next().unwrap();
+    assert!(map.disconnect(id1, id2));
+    let id3 = (0..map.planet_count())
+        .find(|&id| id != 4 && !map.planet(4).neighbors().contains(&id))
+        .unwrap();
+    assert!(map.connect(4, id3));
+
+    let patch =
+        IpsPatch::diff(&bytes, &rom::write_tables(&bytes, &layout(), &map).unwrap()).unwrap();
+    // 書き換えた要素だけがパッチに入る。
+    assert!(patch.records().len() <= 6);
+
+    let (_, planets) = round_trip(&bytes, &map);
+    assert_eq!(rom::diff(&planets, &map, Language::English), []);
+
+    let diffs = rom::diff(&planets, builtin, Language::English);
+    assert!(diffs.contains(&TableDiff::Position {
+        id: 3,
+        rom: Vec3::new(10, 20, 30),
+        map: builtin.planet(3).position(),
+    }));
+    let mut changed: Vec<_> = diffs
+        .iter()
+        .filter_map(|d| match d {
+            TableDiff::Neighbors { id, .. } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    changed.sort_unstable();
+    let mut expected = vec![id1, id2, 4, id3];
+    expected.sort_unstable();
+    expected.dedup();
+    assert_eq!(changed, expected);
+}
+
+#[test]
+fn write_errors() {
+    let bytes = blank_rom();
+    let builtin = StarMap::builtin();
+
+    let mut far = builtin.clone();
+    far.set_position(5, Vec3::new(0, 256, 0));
+    assert!(matches!(
+        rom::write_tables(&bytes, &layout(), &far),
+        Err(RomError::PositionDoesNotFit { id: 5 })
+    ));
+
+    // 終端を含めて 8 バイトの要素に、隣接惑星は 7 つまでしか入らない。
+    let mut crowded = builtin.clone();
+    for id in 1..=8 {
+        crowded.connect(0, id);
+    }
+    assert!(matches!(
+        rom::write_tables(&bytes, &layout(), &crowded),
+        Err(RomError::NeighborsDoNotFit { id: 0 })
+    ));
+
+    let mut fewer = layout();
+    fewer.planet_count = 30;
+    assert!(matches!(
+        rom::write_tables(&bytes, &fewer, builtin),
+        Err(RomError::PlanetCountMismatch {
+            layout: 30,
+            map: 32
+        })
+    ));
+
+    let mut huge_offset = layout();
+    huge_offset.positions.offset = usize::MAX;
+    assert!(matches!(
+        rom::write_tables(&bytes, &huge_offset, builtin),
+        Err(RomError::Truncated("positions"))
+    ));
+
+    assert!(matches!(
+        rom::write_tables(&bytes[..100], &layout(), builtin),
+        Err(RomError::Truncated("PRG ROM"))
+    ));
+}
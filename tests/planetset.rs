@@ -0,0 +1,83 @@
+use logh_travel::planetset::PlanetSet;
+
+#[test]
+fn empty_and_full() {
+    for n in [0, 1, 32, 63, 64, 65, 127, 128, 129, 200] {
+        let empty = PlanetSet::new(n);
+        assert_eq!(empty.universe_len(), n);
+        assert_eq!(empty.len(), 0);
+        assert!(empty.is_empty());
+        assert_eq!(empty.iter().count(), 0);
+
+        let full = PlanetSet::full(n);
+        assert_eq!(full.universe_len(), n);
+        assert_eq!(full.len(), n, "{n}");
+        assert_eq!(full.is_empty(), n == 0);
+        assert!(full.iter().eq(0..n), "{n}");
+    }
+}
+
+#[test]
+fn insert_and_contains() {
+    let mut set = PlanetSet::new(32);
+    assert!(set.insert(5));
+    assert!(!set.insert(5));
+    assert!(set.insert(0));
+    assert!(set.insert(31));
+
+    assert_eq!(set.len(), 3);
+    assert!(!set.is_empty());
+    assert!(set.contains(0) && set.contains(5) && set.contains(31));
+    assert!(!set.contains(1) && !set.contains(30));
+    assert!(set.iter().eq([0, 5, 31]));
+}
+
+/// 先頭 64 惑星分 (head) と残り (tail) の境界。
+#[test]
+fn head_tail_boundary() {
+    let mut set = PlanetSet::new(130);
+    for id in [63, 64, 65, 127, 128, 129] {
+        assert!(set.insert(id), "{id}");
+    }
+
+    assert_eq!(set.len(), 6);
+    assert!(set.iter().eq([63, 64, 65, 127, 128, 129]));
+    for id in [0, 62, 66, 126] {
+        assert!(!set.contains(id), "{id}");
+    }
+
+    // tail にしか要素がなくても空ではない。
+    let mut set = PlanetSet::new(65);
+    assert!(set.insert(64));
+    assert_eq!(set.len(), 1);
+    assert!(!set.is_empty());
+    assert!(!set.contains(63));
+    assert!(set.iter().eq([64]));
+}
+
+#[test]
+fn equality() {
+    let mut a = PlanetSet::new(100);
+    let mut b = PlanetSet::new(100);
+    assert_eq!(a, b);
+
+    a.insert(70);
+    assert_ne!(a, b);
+    b.insert(70);
+    assert_eq!(a, b);
+
+    // 対象とするマップの惑星数が違えば等しくない。
+    assert_ne!(PlanetSet::new(64), PlanetSet::new(65));
+}
+
+#[test]
+#[should_panic(expected = "惑星 ID が範囲外: 64")]
+fn insert_out_of_range_panics() {
+    PlanetSet::new(64).insert(64);
+}
+
+#[test]
+#[should_panic(expected = "惑星 ID が範囲外: 65")]
+fn contains_out_of_range_panics() {
+    PlanetSet::new(65).contains(65);
+}
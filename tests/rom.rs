@@ -0,0 +1,288 @@
+//! 合成した ROM イメージからの表の抽出のテスト。
+//!
+//! ROM は `tests/fixtures/rom_layout.json` の配置に従って組み込みのマップの表を書き込んで作る。
+//! 実際のカートリッジの配置ではない。
+
+use logh_travel::geometry::Vec3;
+use logh_travel::locale::Language;
+use logh_travel::rom::{self, INesRom, RomError, RomLayout, TableDiff};
+use logh_travel::starmap::StarMap;
+
+const PRG_BANKS: u8 = 2;
+const CHR_BANKS: u8 = 1;
+const MAPPER: u8 = 1;
+
+fn layout() -> RomLayout {
+    RomLayout::from_json(include_str!("fixtures/rom_layout.json")).unwrap()
+}
+
+/// 組み込みのマップの表を書き込んだ PRG ROM を返す。
+fn prg(layout: &RomLayout) -> Vec<u8> {
+    let map = StarMap::builtin();
+    let names = layout.names.as_ref().unwrap();
+    let mut prg = vec![0; usize::from(PRG_BANKS) * 16 * 1024];
+
+    for (id, planet) in map.planets().iter().enumerate() {
+        let Vec3 { x, y, z } = planet.position();
+        let offset = layout.positions.offset + id * layout.positions.stride;
+        prg[offset..offset + 3].copy_from_slice(&[x as u8, y as u8, z as u8]);
+
+        let mut neighbors: Vec<_> = planet.neighbors().iter().map(|&id| id as u8).collect();
+        neighbors.push(layout.neighbors.terminator.unwrap());
+        let offset = layout.neighbors.offset + id * layout.neighbors.stride;
+        prg[offset..offset + neighbors.len()].copy_from_slice(&neighbors);
+
+        let mut name: Vec<_> = planet
+            .name(Language::English)
+            .chars()
+            .map(|c| {
+                names
+                    .charset
+                    .iter()
+                    .position(|s| *s == c.to_string())
+                    .unwrap() as u8
+            })
+            .collect();
+        name.push(names.table.terminator.unwrap());
+        let offset = names.table.offset + id * names.table.stride;
+        prg[offset..offset + name.len()].copy_from_slice(&name);
+    }
+
+    prg
+}
+
+/// iNES のヘッダを付けた ROM イメージを返す。
+fn ines(prg: &[u8], trainer: bool) -> Vec<u8> {
+    let mut bytes = b"NES\x1a".to_vec();
+    bytes.extend([
+        PRG_BANKS,
+        CHR_BANKS,
+        (MAPPER << 4) | if trainer { 0x04 } else { 0 },
+        0,
+    ]);
+    bytes.resize(16, 0);
+    if trainer {
+        bytes.extend([0xaa; 512]);
+    }
+    bytes.extend(prg);
+    bytes.extend(vec![0x55; usize::from(CHR_BANKS) * 8 * 1024]);
+
+    bytes
+}
+
+fn extract(prg: &[u8], layout: &RomLayout) -> Vec<rom::RomPlanet> {
+    let rom = INesRom::parse(&ines(prg, false)).unwrap();
+    rom::extract(&rom, layout).unwrap()
+}
+
+#[test]
+fn parse_header() {
+    let prg = prg(&layout());
+
+    for trainer in [false, true] {
+        let rom = INesRom::parse(&ines(&prg, trainer)).unwrap();
+        assert_eq!(rom.mapper(), MAPPER);
+        assert_eq!(rom.prg(), prg);
+        assert_eq!(rom.chr().len(), 8 * 1024);
+        assert!(rom.chr().iter().all(|&b| b == 0x55));
+    }
+}
+
+#[test]
+fn parse_errors() {
+    let bytes = ines(&prg(&layout()), false);
+
+    assert!(matches!(INesRom::parse(b"NES"), Err(RomError::NotINes)));
+    assert!(matches!(
+        INesRom::parse(&bytes[..100]),
+        Err(RomError::Truncated("PRG ROM"))
+    ));
+    assert!(matches!(
+        INesRom::parse(&bytes[..bytes.len() - 1]),
+        Err(RomError::Truncated("CHR ROM"))
+    ));
+
+    let mut not_ines = bytes;
+    not_ines[3] = 0;
+    assert!(matches!(INesRom::parse(&not_ines), Err(RomError::NotINes)));
+}
+
+#[test]
+fn extract_matches_builtin_map() {
+    let layout = layout();
+    let map = StarMap::builtin();
+    let planets = extract(&prg(&layout), &layout);
+
+    assert_eq!(planets.len(), map.planet_count());
+    for (rom, planet) in planets.iter().zip(map.planets()) {
+        assert_eq!(rom.position, planet.position());
+        assert_eq!(rom.neighbors, planet.neighbors());
+        assert_eq!(rom.name.as_deref(), Some(planet.name(Language::English)));
+    }
+    assert_eq!(rom::diff(&planets, map, Language::English), []);
+}
+
+#[test]
+fn diff_reports_every_difference() {
+    let layout = layout();
+    let mut prg = prg(&layout);
+
+    // 惑星 3 の y 座標を変える。
+    prg[layout.positions.offset + 3 * 3 + 1] ^= 1;
+    // 惑星 5 の隣接惑星を 1 つ減らす。
+    let offset = layout.neighbors.offset + 5 * layout.neighbors.stride;
+    let removed = prg[offset];
+    prg[offset..offset + layout.neighbors.stride].rotate_left(1);
+    // 惑星 7 の名前の先頭を空白にする。
+    let names = layout.names.as_ref().unwrap();
+    prg[names.table.offset + 7 * names.table.stride] = 0;
+
+    let map = StarMap::builtin();
+    let diffs = rom::diff(&extract(&prg, &layout), map, Language::English);
+
+    let position = map.planet(3).position();
+    let mut neighbors = map.planet(5).neighbors().to_vec();
+    neighbors.sort_unstable();
+    let mut rom_neighbors = neighbors.clone();
+    rom_neighbors.retain(|&id| id != usize::from(removed));
+    let name = map.planet(7).name(Language::English);
+    assert_eq!(
+        diffs,
+        [
+            TableDiff::Position {
+                id: 3,
+                rom: Vec3 {
+                    y: position.y ^ 1,
+                    ..position
+                },
+                map: position,
+            },
+            TableDiff::Neighbors {
+                id: 5,
+                rom: rom_neighbors,
+                map: neighbors,
+            },
+            TableDiff::Name {
+                id: 7,
+                rom: format!(" {}", &name[1..]),
+                map: name.to_owned(),
+            },
+        ]
+    );
+    assert!(diffs[0].to_string().starts_with("惑星 3 の座標: ROM"));
+}
+
+#[test]
+fn neighbor_order_is_ignored() {
+    let layout = layout();
+    let mut prg = prg(&layout);
+
+    let id = (0..StarMap::builtin().planet_count())
+        .find(|&id| StarMap::builtin().planet(id).neighbors().len() >= 2)
+        .unwrap();
+    let offset = layout.neighbors.offset + id * layout.neighbors.stride;
+    prg.swap(offset, offset + 1);
+
+    let planets = extract(&prg, &layout);
+    assert_ne!(
+        planets[id].neighbors,
+        StarMap::builtin().planet(id).neighbors()
+    );
+    assert_eq!(
+        rom::diff(&planets, StarMap::builtin(), Language::English),
+        []
+    );
+}
+
+#[test]
+fn planet_count_mismatch() {
+    let mut layout = layout();
+    layout.planet_count = 30;
+    let planets = extract(&prg(&layout), &layout);
+
+    assert_eq!(
+        rom::diff(&planets, StarMap::builtin(), Language::English),
+        [TableDiff::PlanetCount { rom: 30, map: 32 }]
+    );
+}
+
+#[test]
+fn names_are_optional() {
+    let mut layout = layout();
+    let prg = prg(&layout);
+    layout.names = None;
+    let planets = extract(&prg, &layout);
+
+    assert!(planets.iter().all(|planet| planet.name.is_none()));
+    assert_eq!(
+        rom::diff(&planets, StarMap::builtin(), Language::Japanese),
+        []
+    );
+}
+
+#[test]
+fn extract_errors() {
+    let layout = layout();
+    let rom = INesRom::parse(&ines(&prg(&layout), false)).unwrap();
+
+    let mut unknown_char = layout.clone();
+    unknown_char.names.as_mut().unwrap().charset.truncate(10);
+    assert!(matches!(
+        rom::extract(&rom, &unknown_char),
+        Err(RomError::UnknownCharacter { id: 0, .. })
+    ));
+
+    let mut unknown_language = layout.clone();
+    unknown_language.names.as_mut().unwrap().language = "xx".to_owned();
+    assert!(matches!(
+        rom::extract(&rom, &unknown_language),
+        Err(RomError::UnknownLanguage(_))
+    ));
+
+    let mut out_of_range = layout.clone();
+    out_of_range.positions.offset = rom.prg().len() - 1;
+    assert!(matches!(
+        rom::extract(&rom, &out_of_range),
+        Err(RomError::Truncated("positions"))
+    ));
+
+    // 配置の JSON の値が大きすぎても、オフセットの計算で溢れずにエラーになる。
+    let mut huge_offset = layout.clone();
+    huge_offset.neighbors.offset = usize::MAX;
+    assert!(matches!(
+        rom::extract(&rom, &huge_offset),
+        Err(RomError::Truncated("neighbors"))
+    ));
+    let mut huge_stride = layout;
+    huge_stride.names.as_mut().unwrap().table.stride = usize::MAX / 2;
+    assert!(matches!(
+        rom::extract(&rom, &huge_stride),
+        Err(RomError::Truncated("names"))
+    ));
+}
+
+#[test]
+fn neighbor_equal_to_terminator_is_rejected() {
+    let mut layout = layout();
+    layout.names = None;
+    layout.neighbors.terminator = Some(31);
+    let bytes = ines(&vec![0; usize::from(PRG_BANKS) * 16 * 1024], false);
+
+    // 終端と同じ ID のまま書き込むと、抽出時にそれ以降の隣接惑星が失われる。
+    let mut map = StarMap::builtin().clone();
+    let first = (0..map.planet_count())
+        .find(|&id| map.planet(id).neighbors().contains(&31))
+        .unwrap();
+    assert!(matches!(
+        rom::write_tables(&bytes, &layout, &map),
+        Err(RomError::NeighborIsTerminator { id, neighbor: 31 }) if id == first
+    ));
+
+    // 終端と同じ ID の隣接がなければ、書き込んだ表をそのまま抽出できる。
+    for id in map.planet(31).neighbors().to_vec() {
+        assert!(map.disconnect(31, id));
+    }
+    let written = rom::write_tables(&bytes, &layout, &map).unwrap();
+    let planets = rom::extract(&INesRom::parse(&written).unwrap(), &layout).unwrap();
+    assert_eq!(rom::diff(&planets, &map, Language::English), []);
+}
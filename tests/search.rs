@@ -0,0 +1,126 @@
+mod common;
+
+use logh_travel::search::{search_planets, MatchKind, PlanetMatch};
+use logh_travel::starmap::StarMap;
+
+/// 検索結果の (ID, 一致の種類) の並び。
+fn search(map: &StarMap, query: &str) -> Vec<(usize, MatchKind)> {
+    search_planets(map, query)
+        .into_iter()
+        .map(|PlanetMatch { id, kind }| (id, kind))
+        .collect()
+}
+
+/// 組み込みのマップで完全一致する惑星の ID。
+fn exact(query: &str) -> Option<usize> {
+    search(StarMap::builtin(), query)
+        .into_iter()
+        .find(|&(_, kind)| kind == MatchKind::Exact)
+        .map(|(id, _)| id)
+}
+
+/// 名前だけの惑星からなるマップ。
+fn named_map(names: &[&str]) -> StarMap {
+    let planets: Vec<_> = (0u32..)
+        .zip(names)
+        .map(|(i, &name)| (name, [i, 0, 0], &[][..], [i * 32, 0]))
+        .collect();
+
+    common::map(&planets)
+}
+
+#[test]
+fn kana_and_width_variants() {
+    for query in [
+        "ハイネセン",
+        "はいねせん",
+        "ﾊｲﾈｾﾝ",
+        "Heinessen",
+        "ＨＥＩＮＥＳＳＥＮ",
+    ] {
+        assert_eq!(exact(query), Some(31), "{query}");
+    }
+    // 半角の濁点は直前の文字と合成する。
+    assert_eq!(exact("ﾊﾞｰﾐﾘｵﾝ"), Some(29));
+    assert_eq!(exact("ハ\u{3099}ーミリオン"), Some(29));
+}
+
+#[test]
+fn separators_are_ignored() {
+    assert_eq!(exact("マルペッサ"), Some(5));
+    assert_eq!(exact("まる・ぺっさ"), Some(5));
+    assert_eq!(exact("marpessa"), Some(5));
+    assert_eq!(exact("Mar Pessa"), Some(5));
+    assert_eq!(exact("バミリオン"), Some(29));
+}
+
+#[test]
+fn romaji() {
+    assert_eq!(exact("hainesen"), Some(31));
+    assert_eq!(exact("hainesenn"), Some(31));
+    assert_eq!(exact("rantemario"), Some(24));
+    assert_eq!(exact("ranntemario"), Some(24));
+    assert_eq!(exact("marupessa"), Some(5));
+    assert_eq!(exact("erufasiru"), Some(28));
+    assert_eq!(exact("erufashiru"), Some(28));
+    assert_eq!(exact("nyukutena"), Some(3));
+    assert_eq!(exact("kyukureusu"), Some(1));
+}
+
+#[test]
+fn romaji_double_n_before_vowel() {
+    let map = named_map(&["コンニチ", "コンイチ", "ハンニョウ"]);
+
+    assert_eq!(search(&map, "konnichi"), [(0, MatchKind::Exact)]);
+    assert_eq!(search(&map, "konnnichi"), [(0, MatchKind::Exact)]);
+    assert_eq!(search(&map, "kon'ichi"), []);
+    // 単独の "n" は後続の母音と組む。
+    assert_eq!(search(&map, "konichi"), [(0, MatchKind::Subsequence)]);
+    assert_eq!(search(&map, "hannyou"), [(2, MatchKind::Exact)]);
+    assert_eq!(search(&map, "hanyou"), [(2, MatchKind::Subsequence)]);
+}
+
+#[test]
+fn ranking() {
+    let map = StarMap::builtin();
+
+    // 完全一致、前方一致、部分一致の順で、同順位なら ID 順。
+    assert_eq!(
+        search(map, "3"),
+        [
+            (3, MatchKind::Exact),
+            (30, MatchKind::Prefix),
+            (31, MatchKind::Prefix),
+            (13, MatchKind::Substring),
+            (23, MatchKind::Substring),
+        ]
+    );
+    assert_eq!(
+        search(map, "mar"),
+        [
+            (5, MatchKind::Prefix),
+            (14, MatchKind::Prefix),
+            (24, MatchKind::Substring),
+            (18, MatchKind::Subsequence),
+        ]
+    );
+    assert_eq!(
+        search(map, "リュ"),
+        [
+            (30, MatchKind::Prefix),
+            (4, MatchKind::Substring),
+            (6, MatchKind::Substring),
+            (25, MatchKind::Substring),
+        ]
+    );
+    assert_eq!(search(map, "ryu"), search(map, "リュ"));
+    // 短い検索語では飛び飛びの一致を判定しない。
+    assert_eq!(search(map, "hs"), []);
+    assert_eq!(search(map, "hsn"), [(31, MatchKind::Subsequence)]);
+}
+
+#[test]
+fn empty_query() {
+    assert_eq!(search(StarMap::builtin(), ""), []);
+    assert_eq!(search(StarMap::builtin(), " ・ー"), []);
+}
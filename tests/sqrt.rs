@@ -0,0 +1,134 @@
+use logh_travel::geometry::{SqrtRoutine, Vec3};
+use logh_travel::sqrtreport;
+use logh_travel::starmap::StarMap;
+use logh_travel::travel::{self, FcRoundedSqrtRules, FcRules, MovementRules};
+
+#[test]
+fn exact_is_floor() {
+    for n in 0..=70000u32 {
+        let r = SqrtRoutine::Exact.sqrt(n);
+        assert!(r * r <= n && n < (r + 1) * (r + 1), "{n}");
+    }
+}
+
+#[test]
+fn rounded_rounds_to_nearest() {
+    // 16 ビットに収まる入力全てと、その外側の一部。
+    for n in (0..=70000u32).chain([u32::MAX / 2, u32::MAX - 1]) {
+        let r = f64::from(n).sqrt().round() as u32;
+        assert_eq!(SqrtRoutine::Rounded.sqrt(n), r, "{n}");
+    }
+}
+
+#[test]
+fn rounded_examples() {
+    let rounded = |n| SqrtRoutine::Rounded.sqrt(n);
+
+    assert_eq!(
+        [rounded(0), rounded(1), rounded(2), rounded(3), rounded(4)],
+        [0, 1, 1, 2, 2]
+    );
+    assert_eq!(
+        [rounded(6), rounded(7), rounded(12), rounded(13)],
+        [2, 3, 3, 4]
+    );
+    // 座標の差が全て 128 のとき。
+    assert_eq!(rounded(3 * 128 * 128), 222);
+    assert_eq!(SqrtRoutine::Exact.sqrt(3 * 128 * 128), 221);
+}
+
+#[test]
+fn delta_disagreements_match_brute_force() {
+    const MAX: u32 = 12;
+
+    let disagreements = sqrtreport::delta_disagreements(MAX);
+
+    let mut expected = vec![];
+    for dx in 0..=MAX {
+        for dy in dx..=MAX {
+            for dz in dy..=MAX {
+                let n = dx * dx + dy * dy + dz * dz;
+                let exact = SqrtRoutine::Exact.sqrt(n);
+                let rounded = SqrtRoutine::Rounded.sqrt(n);
+                if exact != rounded {
+                    expected.push(([dx, dy, dz], exact, rounded));
+                }
+            }
+        }
+    }
+    let actual: Vec<_> = disagreements
+        .iter()
+        .map(|d| (d.delta, d.exact, d.rounded))
+        .collect();
+    assert_eq!(actual, expected);
+
+    // 四捨五入は切り捨てより 1 だけ大きくなる。
+    assert!(disagreements.iter().all(|d| d.rounded == d.exact + 1));
+    assert!(actual.contains(&([1, 1, 1], 1, 2)));
+}
+
+#[test]
+fn rules_switch() {
+    let p1 = Vec3::new(0, 0, 0);
+    let p2 = Vec3::new(1, 1, 1);
+
+    assert_eq!(travel::fc_rules(SqrtRoutine::Exact).distance(p1, p2), 1);
+    assert_eq!(travel::fc_rules(SqrtRoutine::Rounded).distance(p1, p2), 2);
+    assert_eq!(FcRules.distance(p1, p2), 1);
+    assert_eq!(FcRoundedSqrtRules.distance(p1, p2), 2);
+
+    assert!(FcRules.can_order(2, p1, p2));
+    assert!(!FcRoundedSqrtRules.can_order(2, p1, p2));
+    assert_eq!(
+        FcRoundedSqrtRules.actual_turns_and_energy(p1, p2, 30),
+        (1, 2)
+    );
+}
+
+/// 組み込みのマップの惑星の組について、手計算した結果と比べる。
+///
+/// 惑星 0 (8, 8, 8)、1 (8, 16, 24)、2 (16, 8, 16)、3 (8, 32, 32)。隣接は 0-1、0-2、1-2、1-3。
+#[test]
+fn travel_changes_examples() {
+    let map = StarMap::builtin();
+    let changes = sqrtreport::travel_changes(map, 30);
+    let find = |src, dst| {
+        changes
+            .iter()
+            .find(|c| (c.src, c.dst) == (src, dst))
+            .map(|c| (c.exact, c.rounded))
+    };
+
+    // 差 (0, 8, 16)。2 乗和 320 で平方根は 17.9、1 ターンで着く。
+    assert_eq!(find(0, 1), Some(((1, 17), (1, 18))));
+    // 差 (8, 8, 8)。2 乗和 192 で平方根は 13.9。
+    assert_eq!(find(2, 1), Some(((1, 13), (1, 14))));
+    // 差 (24, 8, 16)。2 乗和 896 で平方根は 29.9。四捨五入すると移動力ちょうどで、1 ターンで着く。
+    assert_eq!(find(1, 5), Some(((1, 29), (1, 30))));
+    // 差 (0, 24, 24)。距離は 33 と 34 でどちらも 2 ターン。1 ターン目で (8, 20, 20) に移り、
+    // 各ターンの差 (0, 12, 12) の 2 乗和 288 の平方根 16.97 を 2 回消費する。
+    assert_eq!(find(0, 3), Some(((2, 32), (2, 34))));
+    // 差 (8, 0, 8)。2 乗和 128 で平方根は 11.3 なので、どちらも 11。
+    assert_eq!(find(0, 2), None);
+}
+
+#[test]
+fn order_changes_examples() {
+    let map = StarMap::builtin();
+    let changes = sqrtreport::order_changes(map);
+    let find = |src, dst| {
+        changes
+            .iter()
+            .find(|c| (c.src, c.dst) == (src, dst))
+            .map(|c| (c.exact, c.rounded))
+    };
+
+    // 隣接する惑星へは距離より 1 多いエネルギーが要る。距離は 17 と 18。
+    assert_eq!(find(0, 1), Some((Some(18), Some(19))));
+    // 距離は 13 と 14。
+    assert_eq!(find(1, 2), Some((Some(14), Some(15))));
+    // 惑星 3 へは惑星 1 を経由する。始点からの距離は惑星 1 が 17 と 18、惑星 3 が 33 と 34。
+    assert_eq!(find(0, 3), Some((Some(34), Some(35))));
+    // 距離はどちらも 11。
+    assert_eq!(find(0, 2), None);
+}
@@ -0,0 +1,92 @@
+use logh_travel::geometry::Vec3;
+use logh_travel::starmap::StarMap;
+use logh_travel::travel::{self, FcRules, MovementRules};
+
+/// 距離ちょうどのエネルギーでも移動命令を出せ、移動力の分だけ直進する仮説のルール。
+#[derive(Debug)]
+struct Inclusive;
+
+impl MovementRules for Inclusive {
+    fn can_order(&self, energy: u32, src: Vec3, dst: Vec3) -> bool {
+        energy >= src.distance(dst)
+    }
+
+    fn step(&self, src: Vec3, target: Vec3, speed: u32) -> Vec3 {
+        // x 軸方向のみ動く単純化した移動。テストでは x 軸上の移動にのみ使う。
+        let x = if src.x < target.x {
+            target.x.min(src.x + speed)
+        } else {
+            target.x.max(src.x.saturating_sub(speed))
+        };
+        Vec3 { x, ..src }
+    }
+
+    fn step_energy(&self, _src: Vec3, _dst: Vec3) -> u32 {
+        1
+    }
+}
+
+#[test]
+fn free_functions_use_fc_rules() {
+    let map = StarMap::builtin();
+    let (src, dst) = (0, map.planet_count() - 1);
+    let (p1, p2) = (map.planet(src).position(), map.planet(dst).position());
+
+    assert_eq!(
+        travel::reachable_planets(map, src, 50),
+        FcRules.reachable_planets(map, src, 50)
+    );
+    assert_eq!(
+        travel::reachable_edges(map, src, 50),
+        FcRules.reachable_edges(map, src, 50)
+    );
+    assert_eq!(
+        travel::reachable_planets_and_edges(map, src, 50),
+        (
+            FcRules.reachable_planets(map, src, 50),
+            FcRules.reachable_edges(map, src, 50)
+        )
+    );
+    assert_eq!(
+        travel::min_energy_to_order(map, src, dst),
+        FcRules.min_energy_to_order(map, src, dst)
+    );
+    assert_eq!(
+        travel::actual_turns_and_energy(p1, p2, 20),
+        FcRules.actual_turns_and_energy(p1, p2, 20)
+    );
+    assert_eq!(
+        travel::fleet_path(p1, p2, 20),
+        FcRules.fleet_path(p1, p2, 20)
+    );
+}
+
+#[test]
+fn rules_can_be_compared_side_by_side() {
+    let map = StarMap::builtin();
+    let rules: [&dyn MovementRules; 2] = [&FcRules, &Inclusive];
+
+    for src in 0..map.planet_count() {
+        for &dst in map.planet(src).neighbors() {
+            let distance = map.distance(src, dst);
+            let energies: Vec<_> = rules
+                .iter()
+                .map(|rules| rules.min_energy_to_order(map, src, dst))
+                .collect();
+            assert_eq!(energies, [Some(distance + 1), Some(distance)]);
+        }
+    }
+}
+
+#[test]
+fn custom_step_and_energy() {
+    let src = Vec3::new(0, 0, 0);
+    let target = Vec3::new(25, 0, 0);
+
+    assert_eq!(Inclusive.actual_turns_and_energy(src, target, 10), (3, 3));
+    assert_eq!(
+        Inclusive.fleet_path(src, target, 10),
+        [src, Vec3::new(10, 0, 0), Vec3::new(20, 0, 0), target]
+    );
+    assert_eq!(FcRules.actual_turns_and_energy(src, target, 10), (3, 25));
+}
@@ -0,0 +1,248 @@
+mod common;
+
+use logh_travel::locale::Language;
+use logh_travel::starmap::{MapError, StarMap};
+use logh_travel::validate::{validate, Severity, ViolationKind};
+
+use self::common::map;
+
+fn kinds(map: &StarMap) -> Vec<ViolationKind> {
+    validate(map).into_iter().map(|v| v.kind).collect()
+}
+
+#[test]
+fn builtin_map_has_no_violations() {
+    let violations = validate(StarMap::builtin());
+    assert!(violations.is_empty(), "{violations:#?}");
+}
+
+#[test]
+fn builtin_map_round_trips_through_json() {
+    let map = StarMap::builtin();
+    let loaded = StarMap::from_json(&map.to_json()).unwrap();
+
+    assert_eq!(&loaded, map);
+}
+
+#[test]
+fn valid_map_has_no_violations() {
+    let map = map(&[
+        ("A", [0, 0, 0], &[1], [0, 0]),
+        ("B", [8, 0, 0], &[0, 2], [40, 0]),
+        ("C", [16, 0, 0], &[1], [80, 0]),
+    ]);
+
+    assert_eq!(kinds(&map), []);
+}
+
+#[test]
+fn no_planets() {
+    assert_eq!(kinds(&map(&[])), [ViolationKind::NoPlanets]);
+}
+
+#[test]
+fn neighbor_out_of_range() {
+    let map = map(&[
+        ("A", [0, 0, 0], &[1, 5], [0, 0]),
+        ("B", [8, 0, 0], &[0], [40, 0]),
+    ]);
+
+    assert_eq!(
+        kinds(&map),
+        [ViolationKind::NeighborOutOfRange { id: 0, neighbor: 5 }]
+    );
+}
+
+#[test]
+fn self_loop() {
+    let map = map(&[
+        ("A", [0, 0, 0], &[0, 1], [0, 0]),
+        ("B", [8, 0, 0], &[0], [40, 0]),
+    ]);
+
+    assert_eq!(kinds(&map), [ViolationKind::SelfLoop { id: 0 }]);
+}
+
+#[test]
+fn duplicate_neighbor() {
+    let map = map(&[
+        ("A", [0, 0, 0], &[1, 1], [0, 0]),
+        ("B", [8, 0, 0], &[0], [40, 0]),
+    ]);
+
+    assert_eq!(
+        kinds(&map),
+        [ViolationKind::DuplicateNeighbor { id: 0, neighbor: 1 }]
+    );
+}
+
+#[test]
+fn asymmetric_neighbor() {
+    let map = map(&[
+        ("A", [0, 0, 0], &[1, 2], [0, 0]),
+        ("B", [8, 0, 0], &[0, 2], [40, 0]),
+        ("C", [16, 0, 0], &[1], [80, 0]),
+    ]);
+
+    assert_eq!(
+        kinds(&map),
+        [ViolationKind::Asymmetric { id: 0, neighbor: 2 }]
+    );
+}
+
+#[test]
+fn disconnected_components_are_reported_separately() {
+    let map = map(&[
+        ("A", [0, 0, 0], &[1], [0, 0]),
+        ("B", [8, 0, 0], &[0], [40, 0]),
+        ("C", [16, 0, 0], &[3], [80, 0]),
+        ("D", [24, 0, 0], &[2], [120, 0]),
+        ("E", [32, 0, 0], &[], [160, 0]),
+    ]);
+
+    assert_eq!(
+        kinds(&map),
+        [
+            ViolationKind::Disconnected {
+                planets: vec![2, 3]
+            },
+            ViolationKind::Disconnected { planets: vec![4] },
+        ]
+    );
+}
+
+#[test]
+fn same_positions() {
+    let map = map(&[
+        ("A", [0, 0, 0], &[1], [0, 0]),
+        ("B", [0, 0, 0], &[0], [0, 0]),
+    ]);
+
+    assert_eq!(
+        kinds(&map),
+        [
+            ViolationKind::SamePosition { id1: 0, id2: 1 },
+            ViolationKind::SameMapPosition { id1: 0, id2: 1 },
+        ]
+    );
+}
+
+#[test]
+fn map_position_out_of_bounds() {
+    let map = map(&[
+        ("A", [0, 0, 0], &[1], [500, 0]),
+        ("B", [8, 0, 0], &[0], [0, 460]),
+    ]);
+
+    assert_eq!(
+        kinds(&map),
+        [
+            ViolationKind::MapPositionOutOfBounds { id: 0 },
+            ViolationKind::MapPositionOutOfBounds { id: 1 },
+        ]
+    );
+}
+
+#[test]
+fn huge_map_position_does_not_overflow() {
+    let map = map(&[
+        ("A", [0, 0, 0], &[1], [u32::MAX, 0]),
+        ("B", [8, 0, 0], &[0], [0, u32::MAX - 1]),
+    ]);
+
+    assert_eq!(
+        kinds(&map),
+        [
+            ViolationKind::MapPositionOutOfBounds { id: 0 },
+            ViolationKind::MapPositionOutOfBounds { id: 1 },
+        ]
+    );
+}
+
+#[test]
+fn position_out_of_bounds_prevents_loading() {
+    let map = map(&[
+        ("A", [128, 128, 128], &[1], [0, 0]),
+        ("B", [70000, 0, 0], &[0], [40, 0]),
+    ]);
+
+    assert_eq!(kinds(&map), [ViolationKind::PositionOutOfBounds { id: 1 }]);
+    assert!(matches!(
+        StarMap::from_json(&map.to_json()),
+        Err(MapError::Invalid(_))
+    ));
+    // 検証前のマップでも距離の計算は溢れない。
+    assert_eq!(map.distance(0, 1), 69872);
+}
+
+#[test]
+fn messages_contain_ids_and_names() {
+    let map = map(&[
+        ("アルファ", [0, 0, 0], &[1, 2], [0, 0]),
+        ("ベータ", [8, 0, 0], &[0, 2], [40, 0]),
+        ("ガンマ", [16, 0, 0], &[1], [80, 0]),
+    ]);
+    let violations = validate(&map);
+
+    assert_eq!(violations.len(), 1);
+    let message = violations[0].message(Language::Japanese);
+    assert!(message.contains("0:アルファ"));
+    assert!(message.contains("2:ガンマ"));
+}
+
+#[test]
+fn messages_are_localized() {
+    // 惑星 0 の隣接惑星から 1 つだけ片方向に削除する。
+    let mut json: serde_json::Value = serde_json::from_str(&StarMap::builtin().to_json()).unwrap();
+    let neighbor = json["planets"][0]["neighbors"]
+        .as_array_mut()
+        .unwrap()
+        .remove(0)
+        .as_u64()
+        .unwrap() as usize;
+    let map: StarMap = serde_json::from_value(json).unwrap();
+    let violations = validate(&map);
+
+    assert_eq!(violations.len(), 1);
+    let violation = &violations[0];
+    assert_eq!(
+        violation.kind,
+        ViolationKind::Asymmetric {
+            id: neighbor,
+            neighbor: 0
+        }
+    );
+    let id = neighbor;
+    let neighbor = 0;
+    for lang in Language::iter() {
+        let message = violation.message(lang);
+        assert!(message.contains(&format!("{id}:{}", map.planet(id).name(lang))));
+        assert!(message.contains(&format!("{neighbor}:{}", map.planet(neighbor).name(lang))));
+    }
+    assert!(violation.message(Language::English).is_ascii());
+    assert!(violation.to_string().starts_with("エラー: "));
+}
+
+#[test]
+fn warnings_do_not_prevent_loading() {
+    let map = map(&[
+        ("A", [0, 0, 0], &[], [0, 0]),
+        ("B", [8, 0, 0], &[], [40, 0]),
+    ]);
+    let violations = validate(&map);
+
+    assert!(violations.iter().all(|v| v.severity() == Severity::Warning));
+    assert!(StarMap::from_json(&map.to_json()).is_ok());
+}
+
+#[test]
+fn errors_prevent_loading() {
+    let map = map(&[("A", [0, 0, 0], &[0], [0, 0])]);
+
+    match StarMap::from_json(&map.to_json()) {
+        Err(MapError::Invalid(violations)) => {
+            assert_eq!(violations[0].kind, ViolationKind::SelfLoop { id: 0 });
+        }
+        res => panic!("unexpected result: {res:?}"),
+    }
+}